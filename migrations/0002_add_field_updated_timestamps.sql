@@ -0,0 +1,5 @@
+-- Track when name and email last changed, independently of updated_at
+-- NULL means the field hasn't changed since the user was created
+
+ALTER TABLE users ADD COLUMN IF NOT EXISTS name_updated_at TIMESTAMP WITH TIME ZONE;
+ALTER TABLE users ADD COLUMN IF NOT EXISTS email_updated_at TIMESTAMP WITH TIME ZONE;
@@ -0,0 +1,30 @@
+-- Short, URL-friendly public ids: base62 of a sequence ("1", "2", ..., "Z", "a", ..., "10")
+-- The canonical id stays the UUID; public_id is an alternative lookup key
+
+CREATE SEQUENCE IF NOT EXISTS users_public_id_seq;
+
+CREATE OR REPLACE FUNCTION base62(value BIGINT)
+RETURNS VARCHAR AS $$
+DECLARE
+    alphabet CONSTANT TEXT := '0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz';
+    remaining BIGINT := value;
+    result VARCHAR := '';
+BEGIN
+    IF remaining = 0 THEN
+        RETURN '0';
+    END IF;
+
+    WHILE remaining > 0 LOOP
+        result := substr(alphabet, (remaining % 62)::INT + 1, 1) || result;
+        remaining := remaining / 62;
+    END LOOP;
+
+    RETURN result;
+END;
+$$ language 'plpgsql' IMMUTABLE;
+
+-- The volatile default also fills existing rows, one sequence value each
+ALTER TABLE users ADD COLUMN IF NOT EXISTS public_id VARCHAR(11) UNIQUE
+    DEFAULT base62(nextval('users_public_id_seq'));
+
+ALTER SEQUENCE users_public_id_seq OWNED BY users.public_id;
@@ -0,0 +1,6 @@
+-- Provider-aware canonical email for duplicate detection (EMAIL_CANONICALIZE=true)
+-- e.g. John.Doe+news@gmail.com -> johndoe@gmail.com
+-- NULL when canonicalization was off at write time; those rows are only
+-- checked by the existing unique constraint on email
+
+ALTER TABLE users ADD COLUMN IF NOT EXISTS email_canonical VARCHAR(255) UNIQUE;
@@ -0,0 +1,9 @@
+-- Fixed-window request counters shared by every instance (RATE_LIMIT_BACKEND=postgres)
+-- One row per (key, window); rows from past windows are pruned as keys come back
+
+CREATE TABLE IF NOT EXISTS rate_limit_windows (
+    key VARCHAR(255) NOT NULL,
+    window_start TIMESTAMP WITH TIME ZONE NOT NULL,
+    count INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (key, window_start)
+);
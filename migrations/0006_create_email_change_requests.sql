@@ -0,0 +1,10 @@
+-- Pending email changes: the new address only replaces users.email once the
+-- token sent to it is confirmed. One pending change per user.
+
+CREATE TABLE IF NOT EXISTS email_change_requests (
+    token VARCHAR(64) PRIMARY KEY,
+    user_id UUID NOT NULL UNIQUE REFERENCES users(id) ON DELETE CASCADE,
+    new_email VARCHAR(255) NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
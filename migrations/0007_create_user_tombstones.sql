@@ -0,0 +1,10 @@
+-- Ids of deleted users, so GET can answer 410 Gone instead of 404 Not Found
+-- Rows older than TOMBSTONE_TTL_SECS are ignored and pruned on later deletes
+
+CREATE TABLE IF NOT EXISTS user_tombstones (
+    id UUID PRIMARY KEY,
+    public_id VARCHAR(11),
+    deleted_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS user_tombstones_public_id_idx ON user_tombstones (public_id);
@@ -0,0 +1,4 @@
+-- Past windows are pruned for every key at once (WHERE window_start < ...),
+-- including keys that never come back
+
+CREATE INDEX IF NOT EXISTS rate_limit_windows_window_start_idx ON rate_limit_windows (window_start);
@@ -0,0 +1,7 @@
+-- no-transaction
+-- Drop idx_users_email_lower (added by concurrent migration 1): no query uses
+-- lower(email), lookups go through the unique email / email_canonical indexes,
+-- so it only slowed down writes. Dropped without blocking them.
+-- Keep exactly one statement per file.
+
+DROP INDEX CONCURRENTLY IF EXISTS idx_users_email_lower
@@ -0,0 +1,122 @@
+// Calculator cache - recently computed results, least recently used evicted first
+//
+// Every operation is pure, so an identical request can reuse the earlier
+// result (errors like "Division by zero" included). Entries expire after
+// CALC_CACHE_TTL_SECS; once CALC_CACHE_SIZE entries are stored, the one used
+// longest ago makes room. A size of 0 disables the cache.
+//
+// TypeScript equivalent:
+// const cache = new LRUCache<string, number>({ max: size, ttl });
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::Operation;
+
+// Operands by bit pattern, so NaN and infinities are keys like any other
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CalcKey {
+    op: Operation,
+    a: u64,
+    b: u64,
+}
+
+impl CalcKey {
+    // `double` ignores b. -0.0 and 0.0 stay distinct keys: the sign carries
+    // into results (multiply(-0.0, 1) is -0.0)
+    pub fn new(op: Operation, a: f64, b: f64) -> Self {
+        let b = if op == Operation::Double { 0.0 } else { b };
+        Self {
+            op,
+            a: a.to_bits(),
+            b: b.to_bits(),
+        }
+    }
+}
+
+type CalcResult = Result<f64, String>;
+
+#[derive(Debug)]
+struct Entry {
+    result: CalcResult,
+    stored_at: Instant,
+    // Position in `Lru::order`
+    last_used: u64,
+}
+
+#[derive(Debug, Default)]
+struct Lru {
+    entries: HashMap<CalcKey, Entry>,
+    // last_used tick -> key, oldest first
+    order: BTreeMap<u64, CalcKey>,
+    tick: u64,
+}
+
+#[derive(Debug)]
+pub struct CalcCache {
+    capacity: usize,
+    ttl: Duration,
+    lru: Mutex<Lru>,
+}
+
+impl CalcCache {
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            capacity,
+            ttl,
+            lru: Mutex::new(Lru::default()),
+        }
+    }
+
+    // The cached result if present and fresh, otherwise whatever `compute` returns
+    pub fn get_or_compute(&self, key: CalcKey, compute: impl FnOnce() -> CalcResult) -> CalcResult {
+        if self.capacity == 0 {
+            return compute();
+        }
+
+        let mut lru = self.lru.lock().unwrap();
+        lru.tick += 1;
+        let tick = lru.tick;
+
+        if let Some(entry) = lru.entries.get_mut(&key) {
+            if entry.stored_at.elapsed() < self.ttl {
+                let previous = std::mem::replace(&mut entry.last_used, tick);
+                let result = entry.result.clone();
+                lru.order.remove(&previous);
+                lru.order.insert(tick, key);
+                return result;
+            }
+        }
+
+        // Computing is cheap next to a round trip, so it runs under the lock
+        let result = compute();
+
+        if let Some(stale) = lru.entries.remove(&key) {
+            lru.order.remove(&stale.last_used);
+        }
+        while lru.entries.len() >= self.capacity {
+            let Some((_, oldest)) = lru.order.pop_first() else { break };
+            lru.entries.remove(&oldest);
+        }
+        lru.entries.insert(
+            key,
+            Entry {
+                result: result.clone(),
+                stored_at: Instant::now(),
+                last_used: tick,
+            },
+        );
+        lru.order.insert(tick, key);
+
+        result
+    }
+
+    pub fn len(&self) -> usize {
+        self.lru.lock().unwrap().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
@@ -0,0 +1,544 @@
+// Config module - every setting, parsed and validated once at startup
+//
+// Config::from_env() reads all environment variables up front. Anything
+// missing or malformed is collected into one ConfigErrors list, so a bad
+// deploy reports every problem at once instead of failing on the first, or
+// worse, silently falling back to a default at request time.
+//
+// The parsed Config is passed to create_app, and handlers read their settings
+// from it instead of the environment.
+//
+// TypeScript equivalent:
+// const config = z.object({ DATABASE_URL: z.string(), ... }).parse(process.env);
+
+use std::collections::HashMap;
+use std::fmt;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+use chrono_tz::Tz;
+use sqlx::postgres::PgSslMode;
+
+use crate::db::{self, PoolConfig};
+use crate::models::response::JsonCase;
+use crate::models::timestamp::{parse_timezone, TimestampFormat};
+use crate::models::{IdFormat, OutOfRangePage, SortDirection};
+use crate::rate_limit::{self, RateLimitBackend, RateLimitConfig};
+use crate::server::{ServerSettings, TlsPaths};
+use crate::validation::PasswordPolicy;
+use crate::Operation;
+
+// Longest EMAIL_CHANGE_TTL_SECS accepted (a year)
+pub const MAX_EMAIL_CHANGE_TTL: Duration = Duration::from_secs(365 * 24 * 60 * 60);
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub database: DatabaseConfig,
+    pub server: ServerConfig,
+    pub limits: LimitsConfig,
+    pub features: FeatureConfig,
+}
+
+#[derive(Debug, Clone)]
+pub struct DatabaseConfig {
+    // DATABASE_URL, or built from DB_HOST, DB_PORT, DB_USER, DB_PASSWORD, DB_NAME
+    pub url: String,
+    pub pool: PoolConfig,
+    // DB_HEALTH_CACHE_MS: how long a healthy /health/db result is reused
+    pub health_cache_ttl: Duration,
+    // ACQUIRE_WAIT_WARN_MS: connection waits longer than this are logged
+    pub acquire_wait_warn: Duration,
+}
+
+#[derive(Debug, Clone)]
+pub struct ServerConfig {
+    // HOST and PORT
+    pub bind_addr: SocketAddr,
+    pub settings: ServerSettings,
+    // TLS_CERT_PATH and TLS_KEY_PATH (both or neither)
+    pub tls: Option<TlsPaths>,
+}
+
+#[derive(Debug, Clone)]
+pub struct LimitsConfig {
+    // MAX_CONCURRENT_REQUESTS, USERS_MAX_CONCURRENT_REQUESTS, CALC_MAX_CONCURRENT_REQUESTS
+    pub max_concurrent_requests: Option<usize>,
+    pub users_max_concurrent_requests: Option<usize>,
+    pub calc_max_concurrent_requests: Option<usize>,
+    // RATE_LIMIT_PER_MINUTE, RATE_LIMIT_KEYS, RATE_LIMIT_BACKEND
+    pub rate_limit: Option<RateLimitConfig>,
+    // MAX_OFFSET: the deepest (page - 1) * per_page allowed
+    pub max_offset: i64,
+    // MAX_USERS: total users allowed (None = unlimited)
+    pub max_users: Option<i64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FeatureConfig {
+    pub timestamp_format: TimestampFormat,
+    pub json_case: JsonCase,
+    // DEFAULT_TZ: display zone when the request has no ?tz=
+    pub default_tz: Tz,
+    pub pretty_json: bool,
+    // TRACE_SAMPLE_RATE: fraction of requests that get a request span (4xx/5xx always logged)
+    pub trace_sample_rate: f64,
+    // OMIT_NULLS: leave null fields out of user responses
+    pub omit_nulls: bool,
+    pub default_sort_order: SortDirection,
+    pub out_of_range_page: OutOfRangePage,
+    pub id_format: IdFormat,
+    pub precheck_email: bool,
+    pub email_canonicalize: bool,
+    pub email_autosuffix: bool,
+    // RESERVED_EMAIL_LOCAL_PARTS, lowercased: "postmaster" blocks postmaster@any.domain
+    pub reserved_email_local_parts: Vec<String>,
+    // CALC_ALLOWED_OPS (None = every operation)
+    pub calc_allowed_ops: Option<Vec<Operation>>,
+    // Cached calculator results; a size of 0 disables the cache
+    pub calc_cache_size: usize,
+    pub calc_cache_ttl: Duration,
+    // RESET_TOKEN: enables DELETE /users?confirm=<token>
+    pub reset_token: Option<String>,
+    // ADMIN_TOKEN: enables GET /admin/stats/refresh?confirm=<token>
+    pub admin_token: Option<String>,
+    // EMAIL_CHANGE_TTL_SECS, at most MAX_EMAIL_CHANGE_TTL
+    pub email_change_ttl: Duration,
+    pub tombstone_ttl: Duration,
+    // PASSWORD_MIN_LENGTH, PASSWORD_REQUIRE_DIGIT, PASSWORD_REQUIRE_LETTER
+    pub password_policy: PasswordPolicy,
+}
+
+// Settings other than the database URL, at their defaults
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            database: DatabaseConfig {
+                url: String::new(),
+                pool: PoolConfig::default(),
+                health_cache_ttl: Duration::from_secs(1),
+                acquire_wait_warn: Duration::from_millis(100),
+            },
+            server: ServerConfig {
+                bind_addr: SocketAddr::from(([0, 0, 0, 0], 3000)),
+                settings: ServerSettings::default(),
+                tls: None,
+            },
+            limits: LimitsConfig {
+                max_concurrent_requests: None,
+                users_max_concurrent_requests: None,
+                calc_max_concurrent_requests: None,
+                rate_limit: None,
+                max_offset: 50_000,
+                max_users: None,
+            },
+            features: FeatureConfig {
+                timestamp_format: TimestampFormat::Rfc3339,
+                json_case: JsonCase::Snake,
+                default_tz: chrono_tz::UTC,
+                pretty_json: false,
+                trace_sample_rate: 1.0,
+                omit_nulls: false,
+                default_sort_order: SortDirection::Desc,
+                out_of_range_page: OutOfRangePage::Empty,
+                id_format: IdFormat::UuidV4,
+                precheck_email: false,
+                email_canonicalize: false,
+                email_autosuffix: false,
+                // postmaster (RFC 5321) and abuse (RFC 2142) must reach a person
+                reserved_email_local_parts: ["postmaster", "abuse", "admin", "root"]
+                    .map(String::from)
+                    .to_vec(),
+                calc_allowed_ops: None,
+                calc_cache_size: 1000,
+                calc_cache_ttl: Duration::from_secs(5 * 60),
+                reset_token: None,
+                admin_token: None,
+                email_change_ttl: Duration::from_secs(24 * 60 * 60),
+                tombstone_ttl: Duration::from_secs(30 * 24 * 60 * 60),
+                password_policy: PasswordPolicy::default(),
+            },
+        }
+    }
+}
+
+// ============================================================================
+// Errors
+// ============================================================================
+
+// One missing or invalid variable
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigError {
+    pub var: String,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.var, self.message)
+    }
+}
+
+// Every problem found, in the order the variables were read
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigErrors(pub Vec<ConfigError>);
+
+impl fmt::Display for ConfigErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let lines: Vec<String> = self.0.iter().map(ToString::to_string).collect();
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+impl std::error::Error for ConfigErrors {}
+
+// ============================================================================
+// Parsing
+// ============================================================================
+
+impl Config {
+    pub fn from_env() -> Result<Self, ConfigErrors> {
+        Self::from_lookup(|name| std::env::var(name).ok())
+    }
+
+    // Same as from_env, but reading variables from a map (for tests)
+    pub fn from_vars(vars: &HashMap<String, String>) -> Result<Self, ConfigErrors> {
+        Self::from_lookup(|name| vars.get(name).cloned())
+    }
+
+    fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Result<Self, ConfigErrors> {
+        let mut env = EnvReader::new(lookup);
+        let defaults = Self::default();
+
+        let database = DatabaseConfig {
+            url: env.database_url(),
+            pool: PoolConfig {
+                min_connections: env.parse_at_most(
+                    "DB_MIN_CONNECTIONS",
+                    defaults.database.pool.min_connections,
+                    defaults.database.pool.max_connections,
+                ),
+                test_before_acquire: env
+                    .flag("DB_TEST_BEFORE_ACQUIRE", defaults.database.pool.test_before_acquire),
+                ssl_mode: env.optional::<PgSslMode>("DB_SSLMODE"),
+                ssl_root_cert: env.get("DB_SSL_ROOT_CERT").map(PathBuf::from),
+                application_name: env
+                    .get("DB_APP_NAME")
+                    .unwrap_or_else(|| defaults.database.pool.application_name.clone()),
+                ..defaults.database.pool
+            },
+            health_cache_ttl: env.millis("DB_HEALTH_CACHE_MS", defaults.database.health_cache_ttl),
+            acquire_wait_warn: env.millis("ACQUIRE_WAIT_WARN_MS", defaults.database.acquire_wait_warn),
+        };
+
+        let server = ServerConfig {
+            bind_addr: SocketAddr::new(
+                env.parse("HOST", defaults.server.bind_addr.ip()),
+                env.parse("PORT", defaults.server.bind_addr.port()),
+            ),
+            settings: ServerSettings {
+                header_read_timeout: env.secs(
+                    "HEADER_READ_TIMEOUT_SECS",
+                    defaults.server.settings.header_read_timeout,
+                ),
+                keep_alive: env.flag("HTTP_KEEP_ALIVE", defaults.server.settings.keep_alive),
+                keep_alive_timeout: env.secs(
+                    "HTTP_KEEP_ALIVE_TIMEOUT_SECS",
+                    defaults.server.settings.keep_alive_timeout,
+                ),
+                http2: env.flag("HTTP2", defaults.server.settings.http2),
+            },
+            tls: env.tls_paths(),
+        };
+
+        let limits = LimitsConfig {
+            max_concurrent_requests: env.concurrency_limit("MAX_CONCURRENT_REQUESTS"),
+            users_max_concurrent_requests: env.concurrency_limit("USERS_MAX_CONCURRENT_REQUESTS"),
+            calc_max_concurrent_requests: env.concurrency_limit("CALC_MAX_CONCURRENT_REQUESTS"),
+            rate_limit: env.rate_limit(),
+            max_offset: env.parse_at_least("MAX_OFFSET", defaults.limits.max_offset, 0),
+            max_users: env.optional_at_least("MAX_USERS", 1),
+        };
+
+        let features = FeatureConfig {
+            timestamp_format: env.choice(
+                "TIMESTAMP_FORMAT",
+                defaults.features.timestamp_format,
+                TimestampFormat::parse,
+                "rfc3339 or epoch_ms",
+            ),
+            json_case: env.choice("JSON_CASE", defaults.features.json_case, JsonCase::parse, "snake or camel"),
+            default_tz: env.choice(
+                "DEFAULT_TZ",
+                defaults.features.default_tz,
+                parse_timezone,
+                "an IANA time zone such as America/Sao_Paulo",
+            ),
+            pretty_json: env.flag("PRETTY_JSON", defaults.features.pretty_json),
+            trace_sample_rate: env.choice(
+                "TRACE_SAMPLE_RATE",
+                defaults.features.trace_sample_rate,
+                |value| value.parse().ok().filter(|rate| (0.0..=1.0).contains(rate)),
+                "a number from 0.0 to 1.0",
+            ),
+            omit_nulls: env.flag("OMIT_NULLS", defaults.features.omit_nulls),
+            default_sort_order: env.choice(
+                "DEFAULT_SORT_ORDER",
+                defaults.features.default_sort_order,
+                SortDirection::parse,
+                "asc or desc",
+            ),
+            out_of_range_page: env.choice(
+                "OUT_OF_RANGE_PAGE",
+                defaults.features.out_of_range_page,
+                OutOfRangePage::parse,
+                "empty, clamp or error",
+            ),
+            id_format: env.choice("ID_FORMAT", defaults.features.id_format, IdFormat::parse, "uuidv4, uuidv7 or ulid"),
+            precheck_email: env.flag("PRECHECK_EMAIL", defaults.features.precheck_email),
+            email_canonicalize: env.flag("EMAIL_CANONICALIZE", defaults.features.email_canonicalize),
+            email_autosuffix: env.flag("ENABLE_EMAIL_AUTOSUFFIX", defaults.features.email_autosuffix),
+            reserved_email_local_parts: env
+                .reserved_email_local_parts()
+                .unwrap_or(defaults.features.reserved_email_local_parts),
+            calc_allowed_ops: env.calc_allowed_ops(),
+            calc_cache_size: env.parse("CALC_CACHE_SIZE", defaults.features.calc_cache_size),
+            calc_cache_ttl: env.secs("CALC_CACHE_TTL_SECS", defaults.features.calc_cache_ttl),
+            reset_token: env.get("RESET_TOKEN"),
+            admin_token: env.get("ADMIN_TOKEN"),
+            email_change_ttl: env.secs_at_most(
+                "EMAIL_CHANGE_TTL_SECS",
+                defaults.features.email_change_ttl,
+                MAX_EMAIL_CHANGE_TTL,
+            ),
+            tombstone_ttl: env.secs("TOMBSTONE_TTL_SECS", defaults.features.tombstone_ttl),
+            password_policy: PasswordPolicy {
+                min_length: env.parse_at_least("PASSWORD_MIN_LENGTH", defaults.features.password_policy.min_length, 1),
+                require_digit: env.flag("PASSWORD_REQUIRE_DIGIT", defaults.features.password_policy.require_digit),
+                require_letter: env.flag("PASSWORD_REQUIRE_LETTER", defaults.features.password_policy.require_letter),
+            },
+        };
+
+        env.finish(Self {
+            database,
+            server,
+            limits,
+            features,
+        })
+    }
+}
+
+// Reads variables through `lookup`, recording every problem instead of
+// stopping at the first. Empty values count as unset.
+struct EnvReader<F> {
+    lookup: F,
+    errors: Vec<ConfigError>,
+}
+
+impl<F: Fn(&str) -> Option<String>> EnvReader<F> {
+    fn new(lookup: F) -> Self {
+        Self {
+            lookup,
+            errors: Vec::new(),
+        }
+    }
+
+    fn get(&self, name: &str) -> Option<String> {
+        (self.lookup)(name).filter(|value| !value.trim().is_empty())
+    }
+
+    fn error(&mut self, name: &str, message: impl Into<String>) {
+        self.errors.push(ConfigError {
+            var: name.to_string(),
+            message: message.into(),
+        });
+    }
+
+    fn finish<T>(self, value: T) -> Result<T, ConfigErrors> {
+        if self.errors.is_empty() {
+            Ok(value)
+        } else {
+            Err(ConfigErrors(self.errors))
+        }
+    }
+
+    // Some(value) if set and valid; None (plus an error if malformed) otherwise
+    fn optional<T: FromStr>(&mut self, name: &str) -> Option<T> {
+        let value = self.get(name)?;
+        match value.trim().parse() {
+            Ok(parsed) => Some(parsed),
+            Err(_) => {
+                self.error(name, format!("invalid value '{}'", value));
+                None
+            }
+        }
+    }
+
+    fn parse<T: FromStr>(&mut self, name: &str, default: T) -> T {
+        self.optional(name).unwrap_or(default)
+    }
+
+    // Like optional, but values below `min` are reported (and treated as unset)
+    fn optional_at_least<T: FromStr + PartialOrd + fmt::Display>(&mut self, name: &str, min: T) -> Option<T> {
+        let value = self.optional(name)?;
+        if value < min {
+            self.error(name, format!("must be at least {}", min));
+            return None;
+        }
+        Some(value)
+    }
+
+    // Like parse, but values below `min` are reported (and the default used)
+    fn parse_at_least<T: FromStr + PartialOrd + fmt::Display>(&mut self, name: &str, default: T, min: T) -> T {
+        self.optional_at_least(name, min).unwrap_or(default)
+    }
+
+    // Like parse, but values above `max` are reported (and the default used)
+    fn parse_at_most<T: FromStr + PartialOrd + fmt::Display>(&mut self, name: &str, default: T, max: T) -> T {
+        match self.optional(name) {
+            Some(value) if value > max => {
+                self.error(name, format!("must be at most {}", max));
+                default
+            }
+            Some(value) => value,
+            None => default,
+        }
+    }
+
+    fn choice<T>(&mut self, name: &str, default: T, parse: impl Fn(&str) -> Option<T>, expected: &str) -> T {
+        let Some(value) = self.get(name) else {
+            return default;
+        };
+        parse(value.trim()).unwrap_or_else(|| {
+            self.error(name, format!("expected {}, got '{}'", expected, value));
+            default
+        })
+    }
+
+    fn flag(&mut self, name: &str, default: bool) -> bool {
+        self.choice(
+            name,
+            default,
+            |value| match value {
+                "true" => Some(true),
+                "false" => Some(false),
+                _ => None,
+            },
+            "true or false",
+        )
+    }
+
+    fn secs(&mut self, name: &str, default: Duration) -> Duration {
+        self.optional(name).map(Duration::from_secs).unwrap_or(default)
+    }
+
+    // Like secs, but values above `max` are reported (and the default used)
+    fn secs_at_most(&mut self, name: &str, default: Duration, max: Duration) -> Duration {
+        let value = self.secs(name, default);
+        if value > max {
+            self.error(name, format!("must be at most {} seconds", max.as_secs()));
+            return default;
+        }
+        value
+    }
+
+    fn millis(&mut self, name: &str, default: Duration) -> Duration {
+        self.optional(name).map(Duration::from_millis).unwrap_or(default)
+    }
+
+    fn database_url(&mut self) -> String {
+        if self.get("DB_HOST").is_some() {
+            // Only to report a malformed port; the URL builder reads it again
+            self.optional::<u16>("DB_PORT");
+        }
+
+        db::database_url_from(|name| self.get(name)).unwrap_or_else(|| {
+            self.error("DATABASE_URL", "must be set (or DB_HOST, DB_USER, DB_PASSWORD, DB_NAME)");
+            String::new()
+        })
+    }
+
+    fn tls_paths(&mut self) -> Option<TlsPaths> {
+        match (self.get("TLS_CERT_PATH"), self.get("TLS_KEY_PATH")) {
+            (Some(cert), Some(key)) => Some(TlsPaths {
+                cert: cert.into(),
+                key: key.into(),
+            }),
+            (None, None) => None,
+            (Some(_), None) => {
+                self.error("TLS_KEY_PATH", "must be set when TLS_CERT_PATH is");
+                None
+            }
+            (None, Some(_)) => {
+                self.error("TLS_CERT_PATH", "must be set when TLS_KEY_PATH is");
+                None
+            }
+        }
+    }
+
+    // 0 means no limit, same as unset
+    fn concurrency_limit(&mut self, name: &str) -> Option<usize> {
+        self.optional(name).filter(|&max: &usize| max > 0)
+    }
+
+    // None (no limiting) unless RATE_LIMIT_PER_MINUTE or RATE_LIMIT_KEYS is set
+    fn rate_limit(&mut self) -> Option<RateLimitConfig> {
+        let default_limit = self.optional("RATE_LIMIT_PER_MINUTE");
+
+        let per_key = match self.get("RATE_LIMIT_KEYS") {
+            Some(value) => rate_limit::parse_key_limits(&value).unwrap_or_else(|entry| {
+                self.error("RATE_LIMIT_KEYS", format!("malformed entry '{}', expected key=limit", entry));
+                HashMap::new()
+            }),
+            None => HashMap::new(),
+        };
+
+        let backend = self.choice(
+            "RATE_LIMIT_BACKEND",
+            RateLimitBackend::Memory,
+            RateLimitBackend::parse,
+            "memory or postgres",
+        );
+
+        if default_limit.is_none() && per_key.is_empty() {
+            return None;
+        }
+
+        Some(RateLimitConfig {
+            backend,
+            default_limit,
+            per_key,
+        })
+    }
+
+    // "Admin@, root" -> ["admin", "root"]; a trailing @ is allowed
+    fn reserved_email_local_parts(&mut self) -> Option<Vec<String>> {
+        let value = self.get("RESERVED_EMAIL_LOCAL_PARTS")?;
+
+        Some(
+            value
+                .split(',')
+                .map(|part| part.trim().trim_end_matches('@').to_lowercase())
+                .filter(|part| !part.is_empty())
+                .collect(),
+        )
+    }
+
+    // "add, subtract" -> [Add, Subtract]; every unknown name is reported
+    fn calc_allowed_ops(&mut self) -> Option<Vec<Operation>> {
+        let value = self.get("CALC_ALLOWED_OPS")?;
+        let mut ops = Vec::new();
+
+        for name in value.split(',').map(str::trim).filter(|name| !name.is_empty()) {
+            match Operation::parse(name) {
+                Some(op) => ops.push(op),
+                None => self.error("CALC_ALLOWED_OPS", format!("unknown operation '{}'", name)),
+            }
+        }
+
+        // "," or " , " lists nothing: same as unset (everything allowed), not "nothing allowed"
+        (!ops.is_empty()).then_some(ops)
+    }
+}
+
@@ -0,0 +1,105 @@
+// Connection acquisition with wait-time measurement
+//
+// Under contention, requests queue inside pool.acquire() until a connection
+// frees up. Handlers go through `acquire` instead of passing `&pool` to sqlx,
+// so every wait lands in the app's histogram (AppState::metrics) and slow
+// waits get logged.
+//
+// TypeScript equivalent:
+// const start = performance.now(); const client = await pool.connect();
+// histogram.observe(performance.now() - start);
+
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use sqlx::pool::PoolConnection;
+use sqlx::{PgPool, Postgres};
+
+// Upper bounds (inclusive, in ms) of the histogram buckets; the last bucket is +Inf
+pub const ACQUIRE_WAIT_BUCKETS_MS: [u64; 7] = [1, 5, 10, 50, 100, 500, 1000];
+
+const BUCKET_COUNT: usize = ACQUIRE_WAIT_BUCKETS_MS.len() + 1;
+
+// Wait times of every acquire made through one app's pool
+#[derive(Debug, Default)]
+pub struct AcquireWaits {
+    buckets: [AtomicU64; BUCKET_COUNT],
+    count: AtomicU64,
+    total_us: AtomicU64,
+    max_us: AtomicU64,
+}
+
+// Point-in-time copy of the acquire wait histogram
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AcquireWaitSnapshot {
+    // Non-cumulative counts per bucket, matching ACQUIRE_WAIT_BUCKETS_MS plus +Inf
+    pub buckets: [u64; BUCKET_COUNT],
+    pub count: u64,
+    pub total: Duration,
+    pub max: Duration,
+}
+
+impl AcquireWaits {
+    pub fn snapshot(&self) -> AcquireWaitSnapshot {
+        AcquireWaitSnapshot {
+            buckets: std::array::from_fn(|i| self.buckets[i].load(Ordering::Relaxed)),
+            count: self.count.load(Ordering::Relaxed),
+            total: Duration::from_micros(self.total_us.load(Ordering::Relaxed)),
+            max: Duration::from_micros(self.max_us.load(Ordering::Relaxed)),
+        }
+    }
+
+    fn record(&self, wait: Duration) {
+        let wait_ms = wait.as_millis() as u64;
+        let bucket = ACQUIRE_WAIT_BUCKETS_MS
+            .iter()
+            .position(|&bound| wait_ms <= bound)
+            .unwrap_or(BUCKET_COUNT - 1);
+
+        let wait_us = wait.as_micros() as u64;
+        self.buckets[bucket].fetch_add(1, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.total_us.fetch_add(wait_us, Ordering::Relaxed);
+        self.max_us.fetch_max(wait_us, Ordering::Relaxed);
+    }
+}
+
+// Waits longer than this are logged: the app's ACQUIRE_WAIT_WARN_MS, scoped
+// per request by middleware::app_settings
+tokio::task_local! {
+    static ACQUIRE_WAIT_WARN: Duration;
+}
+
+// Outside of a request (startup, background tasks)
+const DEFAULT_ACQUIRE_WAIT_WARN: Duration = Duration::from_millis(100);
+
+// Run `future` with slow acquires logged past `threshold`
+pub async fn with_acquire_wait_warn<F: Future>(threshold: Duration, future: F) -> F::Output {
+    ACQUIRE_WAIT_WARN.scope(threshold, future).await
+}
+
+fn acquire_wait_warn_threshold() -> Duration {
+    ACQUIRE_WAIT_WARN.try_with(|threshold| *threshold).unwrap_or(DEFAULT_ACQUIRE_WAIT_WARN)
+}
+
+// Check a connection out of the pool, recording how long it took in `waits`
+// Failed acquisitions (e.g. PoolTimedOut) are recorded too: that wait was real
+pub async fn acquire(pool: &PgPool, waits: &AcquireWaits) -> Result<PoolConnection<Postgres>, sqlx::Error> {
+    let started = Instant::now();
+    let result = pool.acquire().await;
+    let wait = started.elapsed();
+
+    waits.record(wait);
+
+    if wait > acquire_wait_warn_threshold() {
+        tracing::warn!(
+            wait_ms = wait.as_millis() as u64,
+            pool_size = pool.size(),
+            idle = pool.num_idle(),
+            "Slow database connection acquire"
+        );
+    }
+
+    result
+}
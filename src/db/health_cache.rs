@@ -0,0 +1,64 @@
+// Health check cache - one database round trip per TTL, however often it's probed
+//
+// Load balancers probe /health/db every second or faster. A healthy result is
+// reused until the TTL passes; a failed one is never cached, so the next probe
+// checks again and recovery shows up immediately. Concurrent probes wait for
+// the check already in progress instead of starting their own.
+//
+// TypeScript equivalent:
+// if (cached && Date.now() - cached.at < ttl) return cached.result;
+
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use tokio::sync::Mutex;
+
+// Outcome of the read (SELECT 1) and write (temp table) checks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DbHealth {
+    pub read: bool,
+    pub write: bool,
+}
+
+impl DbHealth {
+    pub fn is_healthy(&self) -> bool {
+        self.read && self.write
+    }
+}
+
+#[derive(Debug)]
+pub struct HealthCache {
+    ttl: Duration,
+    // When the last healthy result was checked; None after a failure
+    last_healthy: Mutex<Option<(Instant, DbHealth)>>,
+}
+
+impl HealthCache {
+    // A zero TTL checks on every call
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            last_healthy: Mutex::new(None),
+        }
+    }
+
+    // The cached result if it's still fresh, otherwise whatever `check` returns
+    pub async fn get_or_check<F, Fut>(&self, check: F) -> DbHealth
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = DbHealth>,
+    {
+        // Held across the check, so a burst of probes shares one round trip
+        let mut last_healthy = self.last_healthy.lock().await;
+
+        if let Some((checked_at, health)) = *last_healthy {
+            if checked_at.elapsed() < self.ttl {
+                return health;
+            }
+        }
+
+        let health = check().await;
+        *last_healthy = health.is_healthy().then(|| (Instant::now(), health));
+        health
+    }
+}
@@ -0,0 +1,221 @@
+// Error module - API error type and its HTTP response mapping
+//
+// Handlers return Result<T, ApiError>, and ApiError knows how to turn itself
+// into a status code + JSON body. This keeps status decisions in one place.
+//
+// TypeScript equivalent:
+// class ApiError extends Error { constructor(public status: number, message: string) { ... } }
+
+use axum::{
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::error::ErrorKind;
+
+use crate::i18n::{self, Locale};
+use crate::models::ErrorResponse;
+use crate::validation::FieldError;
+
+// Seconds clients should wait before retrying when the pool is saturated
+pub const RETRY_AFTER_SECS: u64 = 1;
+
+#[derive(Debug, Clone)]
+pub enum ApiError {
+    // Free-form validation message (not localized, it usually echoes client input)
+    BadRequest(String),
+    // One entry per invalid field (the field messages aren't localized either)
+    Validation(Vec<FieldError>),
+    // A JSON body that parsed but broke a rule (ValidatedJson); same code, 422
+    InvalidBody(Vec<FieldError>),
+    InvalidResetToken,
+    // GET /users/email-change/confirm with an unknown, used or expired token
+    InvalidEmailChangeToken,
+    NotFound,
+    // GET /users/by-email found nobody with that address
+    EmailNotFound,
+    // Existed, but was deleted (within the tombstone window)
+    Gone,
+    EmailTaken,
+    // Local part on RESERVED_EMAIL_LOCAL_PARTS (postmaster@, abuse@, ...)
+    ReservedEmail,
+    Conflict,
+    ReferenceConflict,
+    InvalidValue,
+    // MAX_USERS reached
+    QuotaExceeded,
+    // ?page= is past the last page (OUT_OF_RANGE_PAGE=error)
+    PageOutOfRange,
+    // The connection pool had no free connection within acquire_timeout
+    ServiceUnavailable,
+    // The pool was closed because the server is shutting down
+    ShuttingDown,
+    // Over the per-key (or per-IP) request quota; retry after this many seconds
+    RateLimited { retry_after_secs: u64 },
+    Internal,
+}
+
+// Stable identifier for each kind of error - the key of the message catalog,
+// and the "code" field of error bodies (UserNotFound -> "USER_NOT_FOUND")
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    BadRequest,
+    ValidationFailed,
+    InvalidResetToken,
+    InvalidEmailChangeToken,
+    UserNotFound,
+    EmailNotFound,
+    UserDeleted,
+    EmailTaken,
+    ReservedEmail,
+    Conflict,
+    ReferenceConflict,
+    InvalidValue,
+    UserQuotaExceeded,
+    PageOutOfRange,
+    ServiceUnavailable,
+    ShuttingDown,
+    RateLimited,
+    Internal,
+}
+
+impl ApiError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            ApiError::BadRequest(_)
+            | ApiError::Validation(_)
+            | ApiError::InvalidValue
+            | ApiError::InvalidEmailChangeToken => StatusCode::BAD_REQUEST,
+            ApiError::InvalidResetToken | ApiError::QuotaExceeded | ApiError::ReservedEmail => {
+                StatusCode::FORBIDDEN
+            }
+            ApiError::InvalidBody(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            ApiError::PageOutOfRange => StatusCode::RANGE_NOT_SATISFIABLE,
+            ApiError::NotFound | ApiError::EmailNotFound => StatusCode::NOT_FOUND,
+            ApiError::Gone => StatusCode::GONE,
+            ApiError::EmailTaken | ApiError::Conflict | ApiError::ReferenceConflict => {
+                StatusCode::CONFLICT
+            }
+            ApiError::ServiceUnavailable | ApiError::ShuttingDown => {
+                StatusCode::SERVICE_UNAVAILABLE
+            }
+            ApiError::RateLimited { .. } => StatusCode::TOO_MANY_REQUESTS,
+            ApiError::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            ApiError::BadRequest(_) => ErrorCode::BadRequest,
+            ApiError::Validation(_) | ApiError::InvalidBody(_) => ErrorCode::ValidationFailed,
+            ApiError::InvalidResetToken => ErrorCode::InvalidResetToken,
+            ApiError::InvalidEmailChangeToken => ErrorCode::InvalidEmailChangeToken,
+            ApiError::NotFound => ErrorCode::UserNotFound,
+            ApiError::EmailNotFound => ErrorCode::EmailNotFound,
+            ApiError::Gone => ErrorCode::UserDeleted,
+            ApiError::EmailTaken => ErrorCode::EmailTaken,
+            ApiError::ReservedEmail => ErrorCode::ReservedEmail,
+            ApiError::Conflict => ErrorCode::Conflict,
+            ApiError::ReferenceConflict => ErrorCode::ReferenceConflict,
+            ApiError::InvalidValue => ErrorCode::InvalidValue,
+            ApiError::QuotaExceeded => ErrorCode::UserQuotaExceeded,
+            ApiError::PageOutOfRange => ErrorCode::PageOutOfRange,
+            ApiError::ServiceUnavailable => ErrorCode::ServiceUnavailable,
+            ApiError::ShuttingDown => ErrorCode::ShuttingDown,
+            ApiError::RateLimited { .. } => ErrorCode::RateLimited,
+            ApiError::Internal => ErrorCode::Internal,
+        }
+    }
+
+    // English message; localized variants are applied by i18n::localize_errors
+    pub fn message(&self) -> String {
+        match self {
+            ApiError::BadRequest(message) => message.clone(),
+            other => i18n::message(other.code(), Locale::En)
+                .unwrap_or_default()
+                .to_string(),
+        }
+    }
+}
+
+// Central sqlx -> HTTP mapping, so handlers can just use `?`
+//
+// - RowNotFound (fetch_one found nothing)  -> 404
+// - unique violation                       -> 409
+// - foreign key violation                  -> 409
+// - check / not-null violation             -> 400
+// - pool timeout (no free connection)      -> 503 + Retry-After
+// - pool closed (shutdown in progress)     -> 503
+// - anything else                          -> 500 (details logged, not returned)
+impl From<sqlx::Error> for ApiError {
+    fn from(error: sqlx::Error) -> Self {
+        match error {
+            sqlx::Error::RowNotFound => ApiError::NotFound,
+            sqlx::Error::Database(db_err) => match db_err.kind() {
+                ErrorKind::UniqueViolation => match db_err.constraint() {
+                    Some("users_email_key" | "users_email_canonical_key") => ApiError::EmailTaken,
+                    _ => ApiError::Conflict,
+                },
+                ErrorKind::ForeignKeyViolation => ApiError::ReferenceConflict,
+                ErrorKind::CheckViolation | ErrorKind::NotNullViolation => ApiError::InvalidValue,
+                _ => {
+                    tracing::error!("Database error: {}", db_err);
+                    ApiError::Internal
+                }
+            },
+            sqlx::Error::PoolTimedOut => {
+                tracing::warn!("Database pool exhausted, returning 503");
+                ApiError::ServiceUnavailable
+            }
+            sqlx::Error::PoolClosed => {
+                tracing::warn!("Database pool closed, server is shutting down");
+                ApiError::ShuttingDown
+            }
+            other => {
+                tracing::error!("Database error: {}", other);
+                ApiError::Internal
+            }
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = self.status();
+        let code = self.code();
+        let fields = match &self {
+            ApiError::Validation(fields) | ApiError::InvalidBody(fields) => Some(fields.clone()),
+            _ => None,
+        };
+        let body = Json(ErrorResponse {
+            error: self.message(),
+            code: Some(code),
+            fields: fields.clone(),
+        });
+
+        let mut response = match self {
+            ApiError::ServiceUnavailable => (
+                status,
+                [(header::RETRY_AFTER, RETRY_AFTER_SECS.to_string())],
+                body,
+            )
+                .into_response(),
+            ApiError::RateLimited { retry_after_secs } => (
+                status,
+                [(header::RETRY_AFTER, retry_after_secs.to_string())],
+                body,
+            )
+                .into_response(),
+            _ => (status, body).into_response(),
+        };
+
+        // Lets middleware (e.g. localization) know which error produced this response
+        response.extensions_mut().insert(code);
+        if let Some(fields) = fields {
+            response.extensions_mut().insert(fields);
+        }
+        response
+    }
+}
@@ -0,0 +1,20 @@
+// Admin handlers - diagnostics for operators
+//
+// - GET /admin/schema  applied migrations vs the ones built into this binary
+//
+// TypeScript equivalent:
+// app.get('/admin/schema', async () => ({ latestVersion, appliedCount, upToDate }));
+
+use std::sync::Arc;
+
+use axum::{extract::State, Json};
+
+use crate::db::{self, SchemaStatus};
+use crate::error::ApiError;
+use crate::state::AppState;
+
+// up_to_date is false when this binary carries migrations the database
+// hasn't applied yet (e.g. a deploy whose migration step failed)
+pub async fn get_schema_status(State(state): State<Arc<AppState>>) -> Result<Json<SchemaStatus>, ApiError> {
+    Ok(Json(db::schema_status(&state.pool, state.metrics.acquire_waits()).await?))
+}
@@ -0,0 +1,180 @@
+// Email change handlers - change a user's email only after the new address confirms
+//
+// - POST /users/:id/email-change          stores the new email + a token (202);
+//                                          users.email is not touched yet
+// - GET  /users/email-change/confirm?token  applies the pending email
+//
+// The old email stays active until confirmation. Tokens expire after
+// EMAIL_CHANGE_TTL_SECS and are single-use.
+//
+// TypeScript equivalent:
+// app.post('/users/:id/email-change', startEmailChange);
+// app.get('/users/email-change/confirm', confirmEmailChange);
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+};
+use chrono::{Duration, Utc};
+use sqlx::Connection;
+use uuid::Uuid;
+
+use crate::db;
+use crate::error::ApiError;
+use crate::models::response::Cased;
+use crate::models::{
+    canonical_email, is_reserved_email, EmailChangeConfirmQuery, EmailChangeRequest, PendingEmailChange,
+    User, UserId,
+};
+use crate::negotiation::{Negotiated, ResponseFormat};
+use crate::state::AppState;
+use crate::validation::ValidatedJson;
+
+// ============================================================================
+// START EMAIL CHANGE - POST /users/:id/email-change
+// ============================================================================
+
+pub async fn start_email_change(
+    format: ResponseFormat,
+    State(state): State<Arc<AppState>>,
+    Path(UserId(id)): Path<UserId>,
+    ValidatedJson(payload): ValidatedJson<EmailChangeRequest>,
+) -> Result<(StatusCode, Negotiated<Cased<PendingEmailChange>>), ApiError> {
+    let new_email = payload.email.trim().to_string();
+    if is_reserved_email(&new_email, &state.config.features.reserved_email_local_parts) {
+        return Err(ApiError::ReservedEmail);
+    }
+
+    let mut conn = db::acquire(&state.pool, state.metrics.acquire_waits()).await?;
+
+    // 404 for unknown users; 409 now rather than at confirmation if the email is taken
+    let exists = sqlx::query_scalar!(
+        r#"SELECT EXISTS(SELECT 1 FROM users WHERE id = $1) AS "exists!""#,
+        id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+    if !exists {
+        return Err(ApiError::NotFound);
+    }
+
+    let email_canonical = state.config.features.email_canonicalize.then(|| canonical_email(&new_email));
+    let taken = sqlx::query_scalar!(
+        r#"
+        SELECT EXISTS(SELECT 1 FROM users WHERE email = $1 OR email_canonical = $2) AS "taken!"
+        "#,
+        new_email,
+        email_canonical
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+    if taken {
+        return Err(ApiError::EmailTaken);
+    }
+
+    let token = Uuid::new_v4().simple().to_string();
+    // EMAIL_CHANGE_TTL_SECS (capped by Config::from_env, so this only fails for a hand-built config)
+    let expires_at = Duration::from_std(state.config.features.email_change_ttl)
+        .ok()
+        .and_then(|ttl| Utc::now().checked_add_signed(ttl))
+        .ok_or(ApiError::Internal)?;
+
+    // A new request replaces any earlier pending one (and invalidates its token)
+    let pending = sqlx::query!(
+        r#"
+        INSERT INTO email_change_requests (token, user_id, new_email, expires_at)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (user_id) DO UPDATE
+            SET token = EXCLUDED.token,
+                new_email = EXCLUDED.new_email,
+                created_at = NOW(),
+                expires_at = EXCLUDED.expires_at
+        RETURNING new_email, expires_at
+        "#,
+        token,
+        id,
+        new_email,
+        expires_at
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    // Stand-in for the confirmation email: there's no mailer in this project yet.
+    // The token itself is never logged - whoever holds it can confirm the change.
+    tracing::info!("Email change requested for user {}; confirmation token issued", id);
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Negotiated(format, Cased(PendingEmailChange {
+            pending_email: pending.new_email,
+            expires_at: pending.expires_at,
+        })),
+    ))
+}
+
+// ============================================================================
+// CONFIRM EMAIL CHANGE - GET /users/email-change/confirm?token=...
+// ============================================================================
+
+pub async fn confirm_email_change(
+    format: ResponseFormat,
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<EmailChangeConfirmQuery>,
+) -> Result<Negotiated<Cased<User>>, ApiError> {
+    let token = query.token.unwrap_or_default();
+
+    let mut conn = db::acquire(&state.pool, state.metrics.acquire_waits()).await?;
+    let mut tx = conn.begin().await?;
+
+    // Single use: the request is consumed whether or not it has expired
+    let pending = sqlx::query!(
+        r#"
+        DELETE FROM email_change_requests
+        WHERE token = $1
+        RETURNING user_id, new_email, expires_at
+        "#,
+        token
+    )
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let Some(pending) = pending else {
+        return Err(ApiError::InvalidEmailChangeToken);
+    };
+
+    if pending.expires_at <= Utc::now() {
+        tx.commit().await?;
+        return Err(ApiError::InvalidEmailChangeToken);
+    }
+
+    let email_canonical = state
+        .config
+        .features
+        .email_canonicalize
+        .then(|| canonical_email(&pending.new_email));
+
+    // Someone may have taken the address since the request: the unique constraint -> 409
+    let user = sqlx::query_as!(
+        User,
+        r#"
+        UPDATE users SET
+            email = $1,
+            email_canonical = $2,
+            email_updated_at = NOW(),
+            updated_at = NOW()
+        WHERE id = $3
+        RETURNING id, public_id, name, email, created_at, updated_at, name_updated_at, email_updated_at
+        "#,
+        pending.new_email,
+        email_canonical,
+        pending.user_id
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(Negotiated(format, Cased(user)))
+}
@@ -1,6 +1,19 @@
 // Handlers module - Request handlers for API endpoints
 
+pub mod admin_handlers;
+pub mod email_change_handlers;
+pub mod stats_handlers;
 pub mod user_handlers;
 
 // Re-export for easier imports
 pub use user_handlers::*;
+
+// True only when the endpoint's token is configured and `given` matches it
+// Compared without short-circuiting, so response timing doesn't leak the token
+pub(crate) fn token_matches(expected: &Option<String>, given: Option<&str>) -> bool {
+    let (Some(expected), Some(given)) = (expected, given) else {
+        return false;
+    };
+    let (a, b) = (expected.as_bytes(), given.as_bytes());
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
@@ -0,0 +1,146 @@
+// Stats handlers - cached aggregate user counts for dashboards
+//
+// Counting the whole table on every dashboard load is wasteful, so the counts
+// are computed on demand and cached in AppState (per app, not across instances):
+// - GET /users/stats          reads the cache (computing it on first use)
+// - GET /admin/stats/refresh  recomputes the cache and returns the new values
+//
+// A refresh runs two full-table queries, so it's guarded like DELETE /users:
+// disabled (403) unless ADMIN_TOKEN is set, and ?confirm= must match it.
+//
+// GET /users/signups is a growth chart's data and is never cached.
+//
+// Counts are by email domain only: users have no role column to group by.
+//
+// TypeScript equivalent:
+// let cache: UserStats | null = null;
+// app.get('/users/stats', async () => cache ??= await computeStats());
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use sqlx::PgConnection;
+
+use crate::db;
+use crate::error::ApiError;
+use crate::models::response::Cased;
+use crate::models::{ResetQuery, SignupBucket, SignupCount, SignupsQuery, UserStats};
+use crate::state::AppState;
+use crate::validation::{parse_timestamp_param, FieldError};
+
+pub async fn get_user_stats(State(state): State<Arc<AppState>>) -> Result<Json<Cased<UserStats>>, ApiError> {
+    let cached = state.cache.user_stats.read().unwrap().clone();
+
+    let stats = match cached {
+        Some(stats) => stats,
+        None => refresh(&state).await?,
+    };
+
+    Ok(Json(Cased(stats)))
+}
+
+pub async fn refresh_user_stats(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ResetQuery>,
+) -> Result<Json<Cased<UserStats>>, ApiError> {
+    if !super::token_matches(&state.config.features.admin_token, query.confirm.as_deref()) {
+        return Err(ApiError::InvalidResetToken);
+    }
+
+    Ok(Json(Cased(refresh(&state).await?)))
+}
+
+async fn refresh(state: &AppState) -> Result<UserStats, ApiError> {
+    let mut conn = db::acquire(&state.pool, state.metrics.acquire_waits()).await?;
+    let stats = compute_stats(&mut conn).await?;
+
+    *state.cache.user_stats.write().unwrap() = Some(stats.clone());
+    tracing::debug!("User stats refreshed: total={}", stats.total);
+
+    Ok(stats)
+}
+
+async fn compute_stats(conn: &mut PgConnection) -> Result<UserStats, sqlx::Error> {
+    let total = sqlx::query_scalar!(r#"SELECT COUNT(*) AS "total!" FROM users"#)
+        .fetch_one(&mut *conn)
+        .await?;
+
+    let by_domain = sqlx::query!(
+        r#"
+        SELECT lower(split_part(email, '@', 2)) AS "domain!", COUNT(*) AS "count!"
+        FROM users
+        GROUP BY 1
+        "#
+    )
+    .fetch_all(&mut *conn)
+    .await?
+    .into_iter()
+    .map(|row| (row.domain, row.count))
+    .collect::<BTreeMap<_, _>>();
+
+    Ok(UserStats {
+        total,
+        by_domain,
+        refreshed_at: chrono::Utc::now(),
+    })
+}
+
+// ============================================================================
+// SIGNUPS OVER TIME - GET /users/signups?bucket=day|week|month&since=2024-01-01
+// ============================================================================
+
+// New users per UTC day, week (starting Monday) or month, oldest first
+// Buckets without signups are left out rather than reported as 0
+//
+// TypeScript equivalent:
+// SELECT date_trunc($bucket, created_at)::date AS date, COUNT(*) FROM users GROUP BY 1 ORDER BY 1
+pub async fn get_signups(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<SignupsQuery>,
+) -> Result<Json<Vec<SignupCount>>, ApiError> {
+    let mut errors = Vec::new();
+
+    let bucket = match query.bucket.as_deref() {
+        None => SignupBucket::Day,
+        Some(value) => SignupBucket::parse(value).unwrap_or_else(|| {
+            errors.push(FieldError::new("bucket", "bucket must be one of day, week, month"));
+            SignupBucket::Day
+        }),
+    };
+
+    let since = match query.since.as_deref().filter(|value| !value.trim().is_empty()) {
+        None => None,
+        Some(value) => parse_timestamp_param("since", value)
+            .map_err(|error| errors.push(error))
+            .ok(),
+    };
+
+    if !errors.is_empty() {
+        return Err(ApiError::Validation(errors));
+    }
+
+    let mut conn = db::acquire(&state.pool, state.metrics.acquire_waits()).await?;
+
+    let signups = sqlx::query_as!(
+        SignupCount,
+        r#"
+        SELECT
+            date_trunc($1, created_at AT TIME ZONE 'UTC')::date AS "date!",
+            COUNT(*) AS "count!"
+        FROM users
+        WHERE $2::timestamptz IS NULL OR created_at >= $2
+        GROUP BY 1
+        ORDER BY 1
+        "#,
+        bucket.as_sql(),
+        since
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    Ok(Json(signups))
+}
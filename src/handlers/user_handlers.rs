@@ -1,111 +1,770 @@
 // User handlers - HTTP request handlers for user CRUD operations
 
+use std::sync::Arc;
+use std::time::Duration;
+
 use axum::{
-    extract::{Path, Query, State},
-    http::StatusCode,
+    body::{Body, Bytes},
+    extract::{Path, Query, RawQuery, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
     Json,
 };
-use sqlx::PgPool;
+use futures::{channel::mpsc, SinkExt, StreamExt};
+use sqlx::{postgres::PgArguments, Arguments, Connection, PgConnection};
 use uuid::Uuid;
 
-use crate::models::{CreateUserRequest, Pagination, UpdateUserRequest, User, UserListResponse};
+use crate::config::Config;
+use crate::db;
+use crate::error::ApiError;
+use crate::models::response::{json_case, with_json_case, Cased};
+use crate::negotiation::{Negotiated, ResponseFormat};
+use crate::models::{nulls, timestamp};
+use crate::models::{
+    canonical_email, is_reserved_email, parse_sort, CreateQuery, CreatedRangeQuery, CreatedUserId, EmailLookupQuery, ReturnPreference, CreateUserRequest, FilterValue, Pagination, PaginationMeta, ResetQuery, SortDirection,
+    ListView, OutOfRangePage, UpdateUserRequest, User, UserFilter, UserId, UserListResponse, UserSiblings,
+    UserSummary, ViewQuery, parse_id,
+};
+use crate::state::AppState;
+use crate::validation::{
+    parse_created_range, validate_pagination, validate_search, FieldError, ValidatedJson, MAX_PER_PAGE,
+};
 
 // ============================================================================
-// CREATE USER - POST /users
+// CREATE USER - POST /users (or POST /users?redirect=true)
 // ============================================================================
 
+// Browser form posts (Accept: text/html or ?redirect=true) get Post/Redirect/Get:
+// 303 See Other to the new user instead of the body, so a refresh doesn't resubmit
+//
+// TypeScript equivalent:
+// if (req.accepts('html') || req.query.redirect) return res.redirect(303, `/users/${user.id}`);
 pub async fn create_user(
-    State(pool): State<PgPool>,
-    Json(payload): Json<CreateUserRequest>,
-) -> Result<(StatusCode, Json<User>), StatusCode> {
-    let user = sqlx::query_as!(
-        User,
-        r#"
-        INSERT INTO users (name, email) 
-        VALUES ($1, $2) 
-         RETURNING id, name, email, created_at, updated_at
-        "#,
-        payload.name,
-        payload.email
-    )
-    .fetch_one(&pool)
-    .await
-    .map_err(|e| {
-        match e.as_database_error() {
-            Some(db_err) if db_err.is_unique_violation() => StatusCode::CONFLICT,
-            _ => StatusCode::INTERNAL_SERVER_ERROR,
+    format: ResponseFormat,
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<CreateQuery>,
+    headers: HeaderMap,
+    ValidatedJson(payload): ValidatedJson<CreateUserRequest>,
+) -> Result<Response, ApiError> {
+    if is_reserved_email(&payload.email, &state.config.features.reserved_email_local_parts) {
+        return Err(ApiError::ReservedEmail);
+    }
+
+    let created = single_flight_create(state, payload, query.return_preference).await?;
+
+    if query.redirect.unwrap_or(false) || accepts_html(&headers) {
+        return Ok((
+            StatusCode::SEE_OTHER,
+            [(header::LOCATION, format!("/users/{}", created.id()))],
+        )
+            .into_response());
+    }
+
+    let body = match created {
+        Created::User(user) => Negotiated(format, Cased(user)).into_response(),
+        Created::Id(id) => Negotiated(format, Cased(CreatedUserId { id })).into_response(),
+    };
+    Ok((StatusCode::CREATED, body).into_response())
+}
+
+// The new user, or only its id for ?return=minimal
+#[derive(Debug, Clone)]
+pub(crate) enum Created {
+    User(User),
+    Id(Uuid),
+}
+
+impl Created {
+    fn id(&self) -> Uuid {
+        match self {
+            Created::User(user) => user.id,
+            Created::Id(id) => *id,
+        }
+    }
+}
+
+// True when Accept lists text/html (with a non-zero q), as browsers do
+fn accepts_html(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(header::ACCEPT)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|accept| accept.split(','))
+        .any(|entry| {
+            let mut parts = entry.split(';').map(str::trim);
+            let is_html = parts
+                .next()
+                .is_some_and(|media_type| media_type.eq_ignore_ascii_case("text/html"));
+            let refused = parts.any(|param| param.strip_prefix("q=").is_some_and(|q| q.parse() == Ok(0.0)));
+            is_html && !refused
+        })
+}
+
+// Keyed by (normalized email, name, return preference)
+pub(crate) type InFlightCreateKey = (String, String, ReturnPreference);
+
+// Double-submitted forms: identical creates that overlap in time collapse into
+// one INSERT, and every caller gets the same user back (all 201, same id).
+// Unlike idempotency keys, nothing is remembered once the insert finishes -
+// a later identical create is a normal duplicate (409).
+async fn single_flight_create(
+    state: Arc<AppState>,
+    payload: CreateUserRequest,
+    returning: ReturnPreference,
+) -> Result<Created, ApiError> {
+    let key = (payload.email.trim().to_lowercase(), payload.name.clone(), returning);
+
+    let work_state = state.clone();
+    let work = async move { create_user_once(&work_state, &payload, returning).await };
+    state.cache.user_creates.run(key, work).await
+}
+
+async fn create_user_once(
+    state: &AppState,
+    payload: &CreateUserRequest,
+    returning: ReturnPreference,
+) -> Result<Created, ApiError> {
+    let config = &state.config;
+    let mut conn = db::acquire(&state.pool, state.metrics.acquire_waits()).await?;
+    let mut result = insert_user(&mut conn, config, &payload.name, &payload.email, returning).await;
+
+    // Opt-in (ENABLE_EMAIL_AUTOSUFFIX): on an email conflict, retry as
+    // alice+1@..., alice+2@..., and so on
+    if config.features.email_autosuffix {
+        for attempt in 1..=MAX_EMAIL_SUFFIX_ATTEMPTS {
+            if !matches!(result, Err(ApiError::EmailTaken)) {
+                break;
+            }
+            let Some(candidate) = suffixed_email(&payload.email, attempt) else {
+                break;
+            };
+            result = insert_user(&mut conn, config, &payload.name, &candidate, returning).await;
         }
-    })?;
+    }
 
-    Ok((StatusCode::CREATED, Json(user)))
+    result
+}
+
+async fn insert_user(
+    conn: &mut PgConnection,
+    config: &Config,
+    name: &str,
+    email: &str,
+    returning: ReturnPreference,
+) -> Result<Created, ApiError> {
+    let mut tx = conn.begin().await?;
+
+    if let Some(max_users) = config.limits.max_users {
+        // Creates take turns while a quota is set, so two concurrent creates
+        // can't both see room for one more user
+        sqlx::query!("SELECT pg_advisory_xact_lock($1)", USER_QUOTA_LOCK_ID)
+            .execute(&mut *tx)
+            .await?;
+
+        let count = sqlx::query_scalar!(r#"SELECT COUNT(*) AS "count!" FROM users"#)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        if count >= max_users {
+            return Err(ApiError::QuotaExceeded);
+        }
+    }
+
+    // Optional early 409 without a failed INSERT. The INSERT's unique constraint
+    // still decides races between concurrent creates.
+    let email_canonical = config.features.email_canonicalize.then(|| canonical_email(email));
+
+    if config.features.precheck_email {
+        let taken = sqlx::query_scalar!(
+            r#"
+            SELECT EXISTS(SELECT 1 FROM users WHERE email = $1 OR email_canonical = $2) AS "taken!"
+            "#,
+            email,
+            email_canonical
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if taken {
+            return Err(ApiError::EmailTaken);
+        }
+    }
+
+    // Both timestamps come from the same NOW(), which is fixed for the whole
+    // transaction, so a never-updated user always has created_at == updated_at
+    let id = config.features.id_format.generate();
+    let created = match returning {
+        ReturnPreference::Representation => Created::User(
+            sqlx::query_as!(
+                User,
+                r#"
+                INSERT INTO users (id, name, email, email_canonical, created_at, updated_at) 
+                VALUES ($1, $2, $3, $4, NOW(), NOW()) 
+                 RETURNING id, public_id, name, email, created_at, updated_at, name_updated_at, email_updated_at
+                "#,
+                id,
+                name,
+                email,
+                email_canonical
+            )
+            .fetch_one(&mut *tx)
+            .await?,
+        ),
+        // Nothing but the id comes back over the wire
+        ReturnPreference::Minimal => Created::Id(
+            sqlx::query_scalar!(
+                r#"
+                INSERT INTO users (id, name, email, email_canonical, created_at, updated_at)
+                VALUES ($1, $2, $3, $4, NOW(), NOW())
+                RETURNING id
+                "#,
+                id,
+                name,
+                email,
+                email_canonical
+            )
+            .fetch_one(&mut *tx)
+            .await?,
+        ),
+    };
+
+    tx.commit().await?;
+
+    Ok(created)
+}
+
+// Arbitrary app-wide key for the advisory lock that serializes quota checks
+const USER_QUOTA_LOCK_ID: i64 = 0x5553_4552_5155_4f54; // "USERQUOT"
+
+const MAX_EMAIL_SUFFIX_ATTEMPTS: u32 = 5;
+
+// "alice@example.com", 2 -> "alice+2@example.com"
+fn suffixed_email(email: &str, attempt: u32) -> Option<String> {
+    let (local, domain) = email.rsplit_once('@')?;
+    Some(format!("{}+{}@{}", local, attempt, domain))
 }
 
 // ============================================================================
-// GET USER - GET /users/:id
+// GET USER - GET /users/:id (UUID, ULID or public_id)
 // ============================================================================
 
 pub async fn get_user(
-    State(pool): State<PgPool>,
-    Path(id): Path<Uuid>,
-) -> Result<Json<User>, StatusCode> {
+    format: ResponseFormat,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Negotiated<Cased<User>>, ApiError> {
+    // The canonical id is the UUID (also accepted as a ULID); anything else is looked up as a public_id
+    let uuid = parse_id(&id);
+    let key = match uuid {
+        Some(uuid) => UserLookup::Id(uuid),
+        None => UserLookup::PublicId(id.clone()),
+    };
+
+    match single_flight_read(state.clone(), key).await? {
+        Some(user) => Ok(Negotiated(format, Cased(user))),
+        None => {
+            // 410 for a user deleted within the tombstone window, 404 otherwise
+            let mut conn = db::acquire(&state.pool, state.metrics.acquire_waits()).await?;
+            if was_deleted(&mut conn, uuid, &id, state.config.features.tombstone_ttl).await? {
+                Err(ApiError::Gone)
+            } else {
+                Err(ApiError::NotFound)
+            }
+        }
+    }
+}
+
+// What GET /users/:id looks a user up by
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) enum UserLookup {
+    Id(Uuid),
+    PublicId(String),
+}
+
+// Thundering herd on one popular user: concurrent reads of the same id share
+// a single SELECT. Like single_flight_create, nothing is cached - a read that
+// starts after the query finished runs its own.
+async fn single_flight_read(state: Arc<AppState>, key: UserLookup) -> Result<Option<User>, ApiError> {
+    let work_state = state.clone();
+    let work_key = key.clone();
+    let work = async move { read_user(&work_state, &work_key).await };
+    state.cache.user_reads.run(key, work).await
+}
+
+async fn read_user(state: &AppState, key: &UserLookup) -> Result<Option<User>, ApiError> {
+    let mut conn = db::acquire(&state.pool, state.metrics.acquire_waits()).await?;
+    state.metrics.record_user_read();
+
+    let user = match key {
+        UserLookup::Id(uuid) => {
+            sqlx::query_as!(
+                User,
+                r#"
+                SELECT id, public_id, name, email, created_at, updated_at, name_updated_at, email_updated_at
+                FROM users 
+                WHERE id = $1
+                "#,
+                uuid
+            )
+            .fetch_optional(&mut *conn)
+            .await?
+        }
+        UserLookup::PublicId(public_id) => {
+            sqlx::query_as!(
+                User,
+                r#"
+                SELECT id, public_id, name, email, created_at, updated_at, name_updated_at, email_updated_at
+                FROM users 
+                WHERE public_id = $1
+                "#,
+                public_id
+            )
+            .fetch_optional(&mut *conn)
+            .await?
+        }
+    };
+
+    Ok(user)
+}
+
+// ============================================================================
+// GET USER BY EMAIL - GET /users/by-email?email=alice@example.com
+// ============================================================================
+
+// Exact match on the stored address (indexed). A miss is EMAIL_NOT_FOUND rather
+// than USER_NOT_FOUND, so clients can tell which lookup came up empty.
+pub async fn get_user_by_email(
+    format: ResponseFormat,
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<EmailLookupQuery>,
+) -> Result<Negotiated<Cased<User>>, ApiError> {
+    let mut conn = db::acquire(&state.pool, state.metrics.acquire_waits()).await?;
 
     let user = sqlx::query_as!(
         User,
         r#"
-        SELECT id, name, email, created_at, updated_at
-        FROM users 
-        WHERE id = $1
+        SELECT id, public_id, name, email, created_at, updated_at, name_updated_at, email_updated_at
+        FROM users
+        WHERE email = $1
         "#,
-        id
+        query.email.trim()
     )
-    .fetch_optional(&pool)
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    user.map(|user| Negotiated(format, Cased(user)))
+        .ok_or(ApiError::EmailNotFound)
+}
+
+// Deleted within `ttl` (TOMBSTONE_TTL_SECS), so GET answers 410 rather than 404
+async fn was_deleted(
+    conn: &mut PgConnection,
+    uuid: Option<Uuid>,
+    public_id: &str,
+    ttl: Duration,
+) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar!(
+        r#"
+        SELECT EXISTS(
+            SELECT 1 FROM user_tombstones
+            WHERE (id = $1 OR public_id = $2)
+              AND deleted_at > NOW() - make_interval(secs => $3)
+        ) AS "deleted!"
+        "#,
+        uuid,
+        public_id,
+        ttl.as_secs_f64()
+    )
+    .fetch_one(&mut *conn)
     .await
-    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?  
-    .ok_or(StatusCode::NOT_FOUND)?; 
-    
-    Ok(Json(user))
 }
 
 // ============================================================================
-// LIST USERS - GET /users?page=1&per_page=10
+// LIST USERS - GET /users?page=1&per_page=10&sort=name:asc&search=alice&snapshot=...
+//              GET /users?id=<uuid>&id=<uuid>
 // ============================================================================
 
 pub async fn list_users(
-    State(pool): State<PgPool>,
+    format: ResponseFormat,
+    State(state): State<Arc<AppState>>,
     Query(pagination): Query<Pagination>,
-) -> Result<Json<UserListResponse>, StatusCode> {
-    let offset = (pagination.page - 1) * pagination.per_page;
-    let total_result = sqlx::query!(
-        "SELECT COUNT(*) as count FROM users"
+    Query(mut filter): Query<UserFilter>,
+    Query(range): Query<CreatedRangeQuery>,
+    Query(view): Query<ViewQuery>,
+    RawQuery(raw_query): RawQuery,
+) -> Result<Response, ApiError> {
+    validate_search(filter.search.as_deref()).map_err(ApiError::Validation)?;
+    (filter.created_after, filter.created_before) =
+        parse_created_range(&range).map_err(ApiError::Validation)?;
+
+    // Explicit ids come back as one page holding every match; page and
+    // per_page are ignored, and unknown ids are simply absent
+    filter.ids = requested_ids(raw_query.as_deref())?;
+    let (mut page, per_page) = match &filter.ids {
+        Some(ids) => (1, ids.len() as i64),
+        None => {
+            validate_pagination(&pagination).map_err(ApiError::Validation)?;
+            (pagination.page, pagination.per_page)
+        }
+    };
+
+    // Validate the sort keys before touching the database
+    let sort_keys = parse_sort(filter.sort.as_deref().unwrap_or(""))
+        .map_err(ApiError::BadRequest)?;
+
+    // id breaks ties, so rows with equal sort values keep a fixed order across pages
+    let order_by = if sort_keys.is_empty() {
+        let direction = match filter.order.as_deref() {
+            Some(order) => SortDirection::parse(order)
+                .ok_or_else(|| ApiError::BadRequest(format!("Unknown sort direction: {}", order)))?,
+            None => state.config.features.default_sort_order,
+        };
+        format!("created_at {0}, id {0}", direction.as_sql())
+    } else {
+        let keys = sort_keys
+            .iter()
+            .map(|(column, direction)| format!("{} {}", column.as_sql(), direction.as_sql()))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("{}, id ASC", keys)
+    };
+
+    // Deep OFFSETs make Postgres read and discard every skipped row
+    // A page so far out that its offset overflows can't hold any rows
+    let mut offset = page_offset(page, per_page).ok_or(ApiError::PageOutOfRange)?;
+    if offset > state.config.limits.max_offset {
+        return Err(ApiError::BadRequest(
+            "pagination window too deep, use cursor pagination".to_string(),
+        ));
+    }
+
+    let mut conn = db::acquire(&state.pool, state.metrics.acquire_waits()).await?;
+
+    // First page of a paging session: capture the snapshot. Rows created after
+    // it are left out of every page that passes it back, so they can't shift offsets.
+    // The database clock, so the token compares cleanly with created_at.
+    let snapshot = match filter.snapshot {
+        Some(snapshot) => snapshot,
+        None => {
+            sqlx::query_scalar!(r#"SELECT NOW() AS "now!""#)
+                .fetch_one(&mut *conn)
+                .await?
+        }
+    };
+    filter.snapshot = Some(snapshot);
+
+    let (where_clause, values) = filter.to_sql_where();
+
+    let total: i64 = sqlx::query_scalar_with(
+        &format!("SELECT COUNT(*) FROM users{}", where_clause),
+        filter_arguments(&values),
     )
-    .fetch_one(&pool)
-    .await
-    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    .fetch_one(&mut *conn)
+    .await?;
+
+    let total_pages = total_pages(total, per_page);
+
+    // Past the last page (an empty dataset still has a page 1)
+    if page > total_pages.max(1) {
+        match state.config.features.out_of_range_page {
+            OutOfRangePage::Empty => {}
+            OutOfRangePage::Clamp => {
+                page = total_pages.max(1);
+                offset = page_offset(page, per_page).ok_or(ApiError::PageOutOfRange)?;
+            }
+            OutOfRangePage::Error => return Err(ApiError::PageOutOfRange),
+        }
+    }
+
+    // ORDER BY can't be a bind parameter, so this query is built at runtime
+    // Safe because order_by only contains whitelisted column names
+    let mut arguments = filter_arguments(&values);
+    arguments.add(per_page);
+    arguments.add(offset);
+
+    // The summary view only reads the columns it sends
+    let columns = match view.view {
+        ListView::Full => {
+            "id, public_id, name, email, created_at, updated_at, name_updated_at, email_updated_at"
+        }
+        ListView::Summary => "id, name, email",
+    };
+
+    let users_sql = format!(
+        "SELECT {} FROM users{} ORDER BY {} LIMIT ${} OFFSET ${}",
+        columns,
+        where_clause,
+        order_by,
+        values.len() + 1,
+        values.len() + 2
+    );
+
+    let response = match view.view {
+        ListView::Full => {
+            let users = sqlx::query_as_with::<_, User, _>(&users_sql, arguments)
+                .fetch_all(&mut *conn)
+                .await?;
+            Negotiated(format, Cased(UserListResponse {
+                users,
+                total,
+                page,
+                per_page,
+                total_pages,
+                snapshot,
+            }))
+            .into_response()
+        }
+        ListView::Summary => {
+            let users = sqlx::query_as_with::<_, UserSummary, _>(&users_sql, arguments)
+                .fetch_all(&mut *conn)
+                .await?;
+            Negotiated(format, Cased(UserListResponse {
+                users,
+                total,
+                page,
+                per_page,
+                total_pages,
+                snapshot,
+            }))
+            .into_response()
+        }
+    };
+
+    Ok(response)
+}
+
+// Every ?id= value (UUID or ULID), or None when there are none
+// Capped like per_page, since they all come back on one page
+fn requested_ids(query: Option<&str>) -> Result<Option<Vec<Uuid>>, ApiError> {
+    let values: Vec<String> = query
+        .unwrap_or("")
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .filter(|(key, _)| *key == "id")
+        .map(|(_, value)| urlencoding::decode(value).map_or_else(|_| value.to_string(), |value| value.into_owned()))
+        .collect();
+
+    if values.is_empty() {
+        return Ok(None);
+    }
+    if values.len() > MAX_PER_PAGE as usize {
+        return Err(ApiError::Validation(vec![FieldError::new(
+            "id",
+            format!("at most {} ids per request", MAX_PER_PAGE),
+        )]));
+    }
+
+    let mut ids = Vec::with_capacity(values.len());
+    let mut errors = Vec::new();
+    for value in values {
+        match parse_id(&value) {
+            Some(id) => ids.push(id),
+            None => errors.push(FieldError::new("id", format!("invalid user id: {}", value))),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(Some(ids))
+    } else {
+        Err(ApiError::Validation(errors))
+    }
+}
+
+// ============================================================================
+// PAGINATION PREVIEW - GET /users/pagination?per_page=20&search=alice
+// ============================================================================
+
+// Same filters as GET /users, but only the COUNT runs - for rendering the
+// pager before the rows load
+pub async fn get_pagination(
+    format: ResponseFormat,
+    State(state): State<Arc<AppState>>,
+    Query(pagination): Query<Pagination>,
+    Query(mut filter): Query<UserFilter>,
+    Query(range): Query<CreatedRangeQuery>,
+) -> Result<Negotiated<Cased<PaginationMeta>>, ApiError> {
+    validate_pagination(&pagination).map_err(ApiError::Validation)?;
+    validate_search(filter.search.as_deref()).map_err(ApiError::Validation)?;
+    (filter.created_after, filter.created_before) =
+        parse_created_range(&range).map_err(ApiError::Validation)?;
+
+    let (where_clause, values) = filter.to_sql_where();
+    let mut conn = db::acquire(&state.pool, state.metrics.acquire_waits()).await?;
+
+    let total: i64 = sqlx::query_scalar_with(
+        &format!("SELECT COUNT(*) FROM users{}", where_clause),
+        filter_arguments(&values),
+    )
+    .fetch_one(&mut *conn)
+    .await?;
 
-    let total = total_result.count.unwrap_or(0); 
+    Ok(Negotiated(format, Cased(PaginationMeta {
+        total,
+        per_page: pagination.per_page,
+        total_pages: total_pages(total, pagination.per_page),
+    })))
+}
+
+// Rows skipped before `page`, or None if that overflows an i64
+fn page_offset(page: i64, per_page: i64) -> Option<i64> {
+    page.checked_sub(1)?.checked_mul(per_page)
+}
 
-    let users = sqlx::query_as!(
+// Pages needed for `total` rows, rounding up (0 rows -> 0 pages)
+fn total_pages(total: i64, per_page: i64) -> i64 {
+    (total + per_page - 1) / per_page
+}
+
+// Bind the filter values to $1, $2, ... in order
+fn filter_arguments(values: &[FilterValue]) -> PgArguments {
+    let mut arguments = PgArguments::default();
+    for value in values {
+        match value {
+            FilterValue::Text(text) => arguments.add(text.clone()),
+            FilterValue::Timestamp(timestamp) => arguments.add(*timestamp),
+            FilterValue::Ids(ids) => arguments.add(ids.clone()),
+        }
+    }
+    arguments
+}
+
+// ============================================================================
+// USER SIBLINGS - GET /users/:id/siblings
+// ============================================================================
+// Previous and next user in the default list order (created_at, then id as a
+// tie-breaker), so a detail view can page through users one at a time.
+// Follows DEFAULT_SORT_ORDER: with the default (desc), "next" is the older user.
+//
+// TypeScript equivalent:
+// const prev = await db.query('SELECT ... WHERE (created_at, id) < ($1, $2) ORDER BY created_at DESC, id DESC LIMIT 1');
+
+pub async fn get_user_siblings(
+    format: ResponseFormat,
+    State(state): State<Arc<AppState>>,
+    Path(UserId(id)): Path<UserId>,
+) -> Result<Negotiated<Cased<UserSiblings>>, ApiError> {
+    let mut conn = db::acquire(&state.pool, state.metrics.acquire_waits()).await?;
+
+    let current = sqlx::query!("SELECT created_at FROM users WHERE id = $1", id)
+        .fetch_one(&mut *conn)
+        .await?;
+
+    let older = sqlx::query_as!(
         User,
         r#"
-        SELECT id, name, email, created_at, updated_at FROM users
-        ORDER BY created_at DESC
-        LIMIT $1 OFFSET $2
+        SELECT id, public_id, name, email, created_at, updated_at, name_updated_at, email_updated_at
+        FROM users
+        WHERE (created_at, id) < ($1, $2)
+        ORDER BY created_at DESC, id DESC
+        LIMIT 1
         "#,
-        pagination.per_page,
-        offset
+        current.created_at,
+        id
     )
-    .fetch_all(&pool)
-    .await
-    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    let newer = sqlx::query_as!(
+        User,
+        r#"
+        SELECT id, public_id, name, email, created_at, updated_at, name_updated_at, email_updated_at
+        FROM users
+        WHERE (created_at, id) > ($1, $2)
+        ORDER BY created_at ASC, id ASC
+        LIMIT 1
+        "#,
+        current.created_at,
+        id
+    )
+    .fetch_optional(&mut *conn)
+    .await?;
 
-    let total_pages = (total + pagination.per_page - 1) / pagination.per_page;
+    let (prev, next) = match state.config.features.default_sort_order {
+        SortDirection::Asc => (older, newer),
+        SortDirection::Desc => (newer, older),
+    };
 
-    Ok(Json(UserListResponse {
-        users, 
-        total, 
-        page: pagination.page, 
-        per_page: pagination.per_page, 
-        total_pages}))
+    Ok(Negotiated(format, Cased(UserSiblings { prev, next })))
+}
+
+// ============================================================================
+// STREAM USERS - GET /users/stream.json
+// ============================================================================
+// Exports every user as one JSON array without buffering the whole table.
+// Rows are read one at a time from sqlx's row stream and written to a chunked
+// response body, so memory stays flat no matter how many users exist.
+//
+// TypeScript equivalent:
+// res.write('['); for await (const row of cursor) res.write(sep + JSON.stringify(row)); res.end(']');
+
+pub async fn stream_users(State(state): State<Arc<AppState>>) -> Result<Response, ApiError> {
+    // Acquire before responding, so an exhausted pool is still a clean 503
+    let mut conn = db::acquire(&state.pool, state.metrics.acquire_waits()).await?;
+
+    // Bounded channel: if the client reads slowly, the DB reader waits too
+    let (mut tx, rx) = mpsc::channel::<Result<Bytes, std::io::Error>>(16);
+
+    // The writer runs on its own task, so carry the request's output settings over
+    let tz = timestamp::display_timezone();
+    let case = json_case();
+    let format = timestamp::timestamp_format();
+    let omit = nulls::omit_nulls();
+
+    let writer = async move {
+        if tx.send(Ok(Bytes::from_static(b"["))).await.is_err() {
+            return;
+        }
+
+        let mut rows = sqlx::query_as!(
+            User,
+            r#"
+            SELECT id, public_id, name, email, created_at, updated_at, name_updated_at, email_updated_at
+            FROM users
+            ORDER BY created_at DESC
+            "#
+        )
+        .fetch(&mut *conn);
+
+        let mut first = true;
+        while let Some(row) = rows.next().await {
+            let chunk = match row.map_err(std::io::Error::other).and_then(|user| {
+                serde_json::to_vec(&Cased(user)).map_err(std::io::Error::other)
+            }) {
+                Ok(json) => {
+                    let mut chunk = Vec::with_capacity(json.len() + 1);
+                    if !first {
+                        chunk.push(b',');
+                    }
+                    chunk.extend_from_slice(&json);
+                    first = false;
+                    Ok(Bytes::from(chunk))
+                }
+                // Status is already sent, so abort the body to signal the failure
+                Err(error) => {
+                    tracing::error!("User stream failed: {}", error);
+                    Err(error)
+                }
+            };
+
+            let failed = chunk.is_err();
+            // A send error means the client went away - stop reading rows
+            if tx.send(chunk).await.is_err() || failed {
+                return;
+            }
+        }
+
+        let _ = tx.send(Ok(Bytes::from_static(b"]"))).await;
+    };
+    let writer = timestamp::with_display_timezone(tz, writer);
+    let writer = timestamp::with_timestamp_format(format, writer);
+    let writer = nulls::with_omit_nulls(omit, writer);
+    tokio::spawn(with_json_case(case, writer));
+
+    Ok((
+        [(header::CONTENT_TYPE, "application/json")],
+        Body::from_stream(rx),
+    )
+        .into_response())
 }
 
 // ============================================================================
@@ -137,28 +796,64 @@ pub async fn list_users(
 //   res.json(user);
 // });
 pub async fn update_user(
-    State(pool): State<PgPool>,
-    Path(id): Path<Uuid>,
-    Json(payload): Json<UpdateUserRequest>,
-) -> Result<Json<User>, StatusCode> {
+    format: ResponseFormat,
+    State(state): State<Arc<AppState>>,
+    Path(UserId(id)): Path<UserId>,
+    ValidatedJson(payload): ValidatedJson<UpdateUserRequest>,
+) -> Result<Negotiated<Cased<User>>, ApiError> {
+    // name and email are NOT NULL: absent means "keep", null is an error
+    let name = non_nullable(payload.name, "name")?;
+    let email = non_nullable(payload.email, "email")?;
+    if email
+        .as_deref()
+        .is_some_and(|email| is_reserved_email(email, &state.config.features.reserved_email_local_parts))
+    {
+        return Err(ApiError::ReservedEmail);
+    }
+
+    // A new email replaces the canonical form too (NULL when canonicalization is off)
+    let email_canonical = email
+        .as_deref()
+        .filter(|_| state.config.features.email_canonicalize)
+        .map(canonical_email);
+
+    let mut conn = db::acquire(&state.pool, state.metrics.acquire_waits()).await?;
+
     let user = sqlx::query_as!(
         User,
         "UPDATE users SET
             name = COALESCE($1, name),
             email = COALESCE($2, email),
+            email_canonical = CASE WHEN $2::VARCHAR IS NOT NULL THEN $4 ELSE email_canonical END,
+            name_updated_at = CASE
+                WHEN $1::VARCHAR IS NOT NULL AND $1 IS DISTINCT FROM name THEN NOW()
+                ELSE name_updated_at
+            END,
+            email_updated_at = CASE
+                WHEN $2::VARCHAR IS NOT NULL AND $2 IS DISTINCT FROM email THEN NOW()
+                ELSE email_updated_at
+            END,
             updated_at = NOW()
         WHERE id = $3
-        RETURNING id, name, email, created_at, updated_at",
-        payload.name,
-        payload.email,
-        id
+        RETURNING id, public_id, name, email, created_at, updated_at, name_updated_at, email_updated_at",
+        name,
+        email,
+        id,
+        email_canonical
     )
-    .fetch_optional(&pool)
-    .await
-    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)? 
-    .ok_or(StatusCode::NOT_FOUND)?;
+    .fetch_one(&mut *conn)
+    .await?;
 
-    Ok(Json(user))
+    Ok(Negotiated(format, Cased(user)))
+}
+
+// A present-but-null value for a field that can't be cleared -> 400
+fn non_nullable(value: Option<Option<String>>, field: &str) -> Result<Option<String>, ApiError> {
+    match value {
+        Some(None) => Err(ApiError::BadRequest(format!("{} cannot be null", field))),
+        Some(value) => Ok(value),
+        None => Ok(None),
+    }
 }
 
 // ============================================================================
@@ -166,34 +861,77 @@ pub async fn update_user(
 // ============================================================================
 
 pub async fn delete_user(
-    State(pool): State<PgPool>,
-    Path(id): Path<Uuid>,
-) -> StatusCode {
-   
+    State(state): State<Arc<AppState>>,
+    Path(UserId(id)): Path<UserId>,
+) -> Result<StatusCode, ApiError> {
+    let mut conn = db::acquire(&state.pool, state.metrics.acquire_waits()).await?;
+
+    // Delete and leave a tombstone in one statement, so GET can answer 410 afterwards
     let result = sqlx::query!(
         r#"
-        DELETE FROM users WHERE id = $1
+        WITH deleted AS (
+            DELETE FROM users WHERE id = $1 RETURNING id, public_id
+        )
+        INSERT INTO user_tombstones (id, public_id)
+        SELECT id, public_id FROM deleted
+        ON CONFLICT (id) DO UPDATE SET deleted_at = NOW()
         "#,
         id,
     )
-    .execute(&pool)
-    .await;
+    .execute(&mut *conn)
+    .await?;
+
+    // Expired tombstones only matter for 410 vs 404, so pruning them here is enough
+    sqlx::query!(
+        "DELETE FROM user_tombstones WHERE deleted_at <= NOW() - make_interval(secs => $1)",
+        state.config.features.tombstone_ttl.as_secs_f64()
+    )
+    .execute(&mut *conn)
+    .await?;
     
-    match result {
-        Ok(query_result) => {
-            if query_result.rows_affected() == 0 {
-                return StatusCode::NOT_FOUND;
-            } else {
-                return StatusCode::NO_CONTENT;
-            }
-        }
-        Err(_) => {
-            return StatusCode::INTERNAL_SERVER_ERROR;
-        }
+    if result.rows_affected() == 0 {
+        Err(ApiError::NotFound)
+    } else {
+        Ok(StatusCode::NO_CONTENT)
     }
    
 }
 
+// ============================================================================
+// DELETE ALL USERS - DELETE /users?confirm=<token>
+// ============================================================================
+// Test-environment reset. Disabled unless RESET_TOKEN is set, and the
+// confirm parameter must match it exactly. Never set RESET_TOKEN in production.
+
+pub async fn delete_all_users(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ResetQuery>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    if !super::token_matches(&state.config.features.reset_token, query.confirm.as_deref()) {
+        return Err(ApiError::InvalidResetToken);
+    }
+
+    let mut conn = db::acquire(&state.pool, state.metrics.acquire_waits()).await?;
+
+    // Tombstones for everyone, like a single delete, so GET answers 410 afterwards
+    let result = sqlx::query!(
+        r#"
+        WITH deleted AS (
+            DELETE FROM users RETURNING id, public_id
+        )
+        INSERT INTO user_tombstones (id, public_id)
+        SELECT id, public_id FROM deleted
+        ON CONFLICT (id) DO UPDATE SET deleted_at = NOW()
+        "#
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    tracing::warn!("Reset endpoint deleted {} users", result.rows_affected());
+
+    Ok(Json(serde_json::json!({ "deleted": result.rows_affected() })))
+}
+
 // ============================================================================
 // BONUS: Add input validation, better error handling, logging
 // ============================================================================
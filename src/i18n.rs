@@ -0,0 +1,197 @@
+// i18n module - Localized error messages selected by Accept-Language
+//
+// A small static catalog keyed by ErrorCode. Supported locales: en, es, pt.
+// Anything else falls back to English.
+//
+// TypeScript equivalent:
+// const messages: Record<ErrorCode, Record<Locale, string>> = { ... };
+
+use axum::{
+    body::Body,
+    extract::Request,
+    http::header,
+    middleware::Next,
+    response::Response,
+};
+
+use crate::error::ErrorCode;
+use crate::models::ErrorResponse;
+use crate::validation::FieldError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Locale {
+    En,
+    Es,
+    Pt,
+}
+
+impl Locale {
+    fn from_tag(tag: &str) -> Option<Self> {
+        // Only the primary subtag matters: "pt-BR" -> "pt"
+        let primary = tag.split('-').next().unwrap_or("").trim().to_ascii_lowercase();
+        match primary.as_str() {
+            "en" => Some(Locale::En),
+            "es" => Some(Locale::Es),
+            "pt" => Some(Locale::Pt),
+            _ => None,
+        }
+    }
+
+    // Pick the supported locale with the highest q-value
+    // Example: "fr-FR, pt;q=0.8, en;q=0.5" -> Pt
+    pub fn from_accept_language(header: &str) -> Self {
+        let mut best: Option<(Locale, f32)> = None;
+
+        for entry in header.split(',') {
+            let mut parts = entry.split(';');
+            let tag = parts.next().unwrap_or("");
+            let quality = parts
+                .find_map(|param| param.trim().strip_prefix("q="))
+                .and_then(|q| q.parse::<f32>().ok())
+                .unwrap_or(1.0);
+
+            if let Some(locale) = Locale::from_tag(tag) {
+                if quality > 0.0 && best.is_none_or(|(_, q)| quality > q) {
+                    best = Some((locale, quality));
+                }
+            }
+        }
+
+        best.map(|(locale, _)| locale).unwrap_or(Locale::En)
+    }
+}
+
+// None means the message is dynamic (e.g. BadRequest echoing client input)
+// Every message is lowercase, in every locale ("user not found")
+pub fn message(code: ErrorCode, locale: Locale) -> Option<&'static str> {
+    let (en, es, pt) = match code {
+        ErrorCode::BadRequest => return None,
+        ErrorCode::ValidationFailed => (
+            "validation failed",
+            "la validación falló",
+            "a validação falhou",
+        ),
+        ErrorCode::InvalidResetToken => (
+            "invalid or missing confirmation token",
+            "token de confirmación no válido o ausente",
+            "token de confirmação inválido ou ausente",
+        ),
+        ErrorCode::InvalidEmailChangeToken => (
+            "invalid or expired email change token",
+            "token de cambio de correo electrónico no válido o caducado",
+            "token de alteração de e-mail inválido ou expirado",
+        ),
+        ErrorCode::UserNotFound => (
+            "user not found",
+            "usuario no encontrado",
+            "usuário não encontrado",
+        ),
+        ErrorCode::EmailNotFound => (
+            "no user with that email",
+            "ningún usuario con ese correo electrónico",
+            "nenhum usuário com esse e-mail",
+        ),
+        ErrorCode::UserDeleted => (
+            "user has been deleted",
+            "el usuario ha sido eliminado",
+            "o usuário foi excluído",
+        ),
+        ErrorCode::EmailTaken => (
+            "email already exists",
+            "el correo electrónico ya existe",
+            "o e-mail já está em uso",
+        ),
+        ErrorCode::ReservedEmail => (
+            "reserved email",
+            "correo electrónico reservado",
+            "e-mail reservado",
+        ),
+        ErrorCode::Conflict => (
+            "resource already exists",
+            "el recurso ya existe",
+            "o recurso já existe",
+        ),
+        ErrorCode::ReferenceConflict => (
+            "referenced resource conflict",
+            "conflicto con un recurso relacionado",
+            "conflito com um recurso relacionado",
+        ),
+        ErrorCode::InvalidValue => (
+            "invalid value for a constrained field",
+            "valor no válido para un campo restringido",
+            "valor inválido para um campo restrito",
+        ),
+        ErrorCode::UserQuotaExceeded => (
+            "user quota exceeded",
+            "se superó la cuota de usuarios",
+            "cota de usuários excedida",
+        ),
+        ErrorCode::PageOutOfRange => (
+            "page out of range",
+            "página fuera de rango",
+            "página fora do intervalo",
+        ),
+        ErrorCode::ServiceUnavailable => (
+            "service temporarily unavailable",
+            "servicio no disponible temporalmente",
+            "serviço temporariamente indisponível",
+        ),
+        ErrorCode::ShuttingDown => (
+            "server shutting down",
+            "el servidor se está apagando",
+            "o servidor está sendo desligado",
+        ),
+        ErrorCode::RateLimited => (
+            "too many requests",
+            "demasiadas solicitudes",
+            "muitas requisições",
+        ),
+        ErrorCode::Internal => (
+            "internal server error",
+            "error interno del servidor",
+            "erro interno do servidor",
+        ),
+    };
+
+    Some(match locale {
+        Locale::En => en,
+        Locale::Es => es,
+        Locale::Pt => pt,
+    })
+}
+
+// Middleware: rewrite ApiError bodies into the client's language
+// ApiError responses carry their ErrorCode in the response extensions
+pub async fn localize_errors(request: Request, next: Next) -> Response {
+    let locale = request
+        .headers()
+        .get(header::ACCEPT_LANGUAGE)
+        .and_then(|value| value.to_str().ok())
+        .map(Locale::from_accept_language)
+        .unwrap_or(Locale::En);
+
+    let mut response = next.run(request).await;
+
+    if locale == Locale::En {
+        return response;
+    }
+
+    let localized = response
+        .extensions()
+        .get::<ErrorCode>()
+        .and_then(|&code| Some((code, message(code, locale)?)));
+
+    // Only the message is translated; the code and field errors stay the same in every locale
+    if let Some((code, error)) = localized {
+        let body = serde_json::to_vec(&ErrorResponse {
+            error: error.to_string(),
+            code: Some(code),
+            fields: response.extensions().get::<Vec<FieldError>>().cloned(),
+        })
+            .expect("ErrorResponse always serializes");
+        response.headers_mut().remove(header::CONTENT_LENGTH);
+        *response.body_mut() = Body::from(body);
+    }
+
+    response
+}
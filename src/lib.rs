@@ -1,34 +1,157 @@
 // Module declarations
+pub mod calc_cache;
+pub mod config;
 pub mod db;
+pub mod error;
 pub mod handlers;
+pub mod i18n;
+pub mod middleware;
 pub mod models;
+pub mod negotiation;
+pub mod rate_limit;
+pub mod server;
+pub mod single_flight;
+pub mod startup;
+pub mod state;
+pub mod validation;
 
 // Imports
+use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
 use sqlx::PgPool;
 use axum::extract::{Request, State};
 use axum::http::StatusCode;
+use tower::limit::GlobalConcurrencyLimitLayer;
+use tower_http::catch_panic::CatchPanicLayer;
 use tower_http::trace::TraceLayer;
 use serde::{Deserialize, Serialize};
 use axum::{
-    extract::Query,
+    extract::{
+        rejection::{JsonRejection, QueryRejection},
+        Query,
+    },
     response::Json,
     routing::{get, post, put, delete},
     Router,
 };
-use tracing::info_span;
-use handlers::user_handlers;
+use tracing::{info_span, Span};
+use std::sync::Arc;
+use config::Config;
+use state::AppState;
+use handlers::{admin_handlers, email_change_handlers, stats_handlers, user_handlers};
+
+// TypeScript equivalent:
+// type Operation = 'add' | 'subtract' | 'multiply' | 'divide' | 'modulo' | 'power' | 'double';
+// Deserialized case-insensitively ("ADD", "Add" and "add" all work), serialized lowercase
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Operation {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Modulo,
+    Power,
+    Double,
+}
+
+impl Operation {
+    pub const ALL: [Operation; 7] = [
+        Operation::Add,
+        Operation::Subtract,
+        Operation::Multiply,
+        Operation::Divide,
+        Operation::Modulo,
+        Operation::Power,
+        Operation::Double,
+    ];
+
+    // Listed in "expected one of" errors
+    const NAMES: &'static [&'static str] =
+        &["add", "subtract", "multiply", "divide", "modulo", "power", "double"];
+
+    // Case-insensitive: "Divide" -> Some(Operation::Divide)
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|op| op.as_str().eq_ignore_ascii_case(name))
+    }
+
+    // The canonical (lowercase) name, e.g. for CALC_ALLOWED_OPS
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Operation::Add => "add",
+            Operation::Subtract => "subtract",
+            Operation::Multiply => "multiply",
+            Operation::Divide => "divide",
+            Operation::Modulo => "modulo",
+            Operation::Power => "power",
+            Operation::Double => "double",
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Operation {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        RequestedOperation::deserialize(deserializer).map(|requested| requested.operation)
+    }
+}
+
+// An operation as the client spelled it, e.g. "ADD" for older clients:
+// `operation` is what runs, `spelling` is echoed back in the response
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestedOperation {
+    pub operation: Operation,
+    pub spelling: String,
+}
+
+impl<'de> Deserialize<'de> for RequestedOperation {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let spelling = String::deserialize(deserializer)?;
+        let operation = Operation::parse(&spelling)
+            .ok_or_else(|| serde::de::Error::unknown_variant(&spelling, Operation::NAMES))?;
+        Ok(Self { operation, spelling })
+    }
+}
 
 // TypeScript equivalent:
 // interface CalculatorRequest {
 //   a: number;
 //   b: number;
-//   op: string;
+//   op: Operation;
 // }
 #[derive(Deserialize)]
 pub struct CalculatorRequest {
     a: f64,
     b: f64,
-    op: String,
+    // Unknown operations fail deserialization, so they never reach a handler
+    op: RequestedOperation,
+    // ?int=true: integer modulo instead of float fmod (other operations ignore it)
+    #[serde(default)]
+    int: bool,
+}
+
+impl CalculatorRequest {
+    // The operands compute() gets: as sent, or truncated for integer modulo
+    fn operands(&self) -> Result<(f64, f64), String> {
+        match self.op.operation {
+            Operation::Modulo if self.int => integer_operands(self.a, self.b),
+            _ => Ok((self.a, self.b)),
+        }
+    }
+}
+
+// Operands for integer modulo, truncated toward zero, if both fit in an i64
+// fmod is exact on whole numbers, so compute() then gives the integer
+// remainder: 10.5 % 3 is 1 instead of 1.5
+// TypeScript equivalent: Math.trunc(a) % Math.trunc(b)
+pub fn integer_operands(a: f64, b: f64) -> Result<(f64, f64), String> {
+    // i64::MAX as f64 rounds up to 2^63, which itself doesn't fit
+    let fits = |value: f64| value >= i64::MIN as f64 && value < i64::MAX as f64;
+    let (a, b) = (a.trunc(), b.trunc());
+
+    if fits(a) && fits(b) {
+        Ok((a, b))
+    } else {
+        Err("Integer modulo requires operands that fit in a 64-bit integer".to_string())
+    }
 }
 
 // TypeScript equivalent:
@@ -54,40 +177,144 @@ pub struct ErrorResponse {
 // TypeScript equivalent:
 // async function calculate(req: CalculatorRequest): Promise<CalculatorResponse | ErrorResponse>
 pub async fn calculate(
-    Query(params): Query<CalculatorRequest>,
-) -> Json<serde_json::Value> {
+    State(state): State<Arc<AppState>>,
+    query: Result<Query<CalculatorRequest>, QueryRejection>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<ErrorResponse>)> {
+    // Bad input (including an unknown op) gets our JSON 400, not axum's plain-text one
+    let Query(params) = query.map_err(|rejection| bad_request(rejection.body_text()))?;
+
+    let op = params.op.operation;
+    if !operation_allowed(&state.config, op) {
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse { error: "operation disabled".to_string() }),
+        ));
+    }
+
+    let (a, b) = params.operands().map_err(bad_request)?;
+
+    match cached_compute(&state, op, a, b) {
+        Ok(result) => Ok(Json(serde_json::json!(CalculatorResponse {
+            result,
+            // Echoed as sent, so a client sending "ADD" gets "ADD" back
+            operation: params.op.spelling
+        }))),
+        Err(error) => Ok(Json(serde_json::json!(ErrorResponse { error }))),
+    }
+}
+
+fn bad_request(error: String) -> (StatusCode, Json<ErrorResponse>) {
+    (StatusCode::BAD_REQUEST, Json(ErrorResponse { error }))
+}
+
+// The arithmetic itself, shared by /calculate and /calculate/batch
+// Errors are for valid operations on invalid operands (e.g. dividing by zero)
+pub fn compute(op: Operation, a: f64, b: f64) -> Result<f64, String> {
     // Pattern matching - like switch on steroids
-    // Much more powerful than TypeScript's switch statement
-    let result = match params.op.as_str() {
-        "add" => params.a + params.b,
-        "subtract" => params.a - params.b,
-        "multiply" => params.a * params.b,
-        "divide" => {
-            if params.b == 0.0 {
-                return Json(serde_json::json!(ErrorResponse {
-                    error: "Division by zero".to_string()
-                }));
+    // Much more powerful than TypeScript's switch statement.
+    // The match is exhaustive: a new Operation variant won't compile until it's handled here.
+    let result = match op {
+        Operation::Add => a + b,
+        Operation::Subtract => a - b,
+        Operation::Multiply => a * b,
+        Operation::Divide => {
+            if b == 0.0 {
+                return Err("Division by zero".to_string());
             }
-            params.a / params.b
+            a / b
         }
-        "modulo" => params.a % params.b,
-        "power" => {
-            if params.b < 0.0 {
-                return Json(serde_json::json!(ErrorResponse {
-                    error: "Power operation requires a positive exponent".to_string()
-                }));
+        // Float remainder (fmod), sign of a: 10.5 % 3 = 1.5, -7 % 3 = -1
+        // ?int=true truncates the operands first (see integer_operands)
+        Operation::Modulo => {
+            if b == 0.0 {
+                return Err("Modulo by zero".to_string());
             }
-            params.a.powf(params.b)
-        },
-        "double" => params.a * 2.0,
-        _ => {
-            return Json(serde_json::json!(ErrorResponse {
-                error: format!("Unknown operation: {}", params.op)
-            }));
+            a % b
         }
+        Operation::Power => {
+            if b < 0.0 {
+                return Err("Power operation requires a positive exponent".to_string());
+            }
+            a.powf(b)
+        },
+        Operation::Double => a * 2.0,
     };
 
-    Json(serde_json::json!(CalculatorResponse { result, operation: params.op }))
+    Ok(result)
+}
+
+// compute() behind the calculator cache (CALC_CACHE_SIZE, CALC_CACHE_TTL_SECS)
+fn cached_compute(state: &AppState, op: Operation, a: f64, b: f64) -> Result<f64, String> {
+    state.cache.calculator.get_or_compute(calc_cache::CalcKey::new(op, a, b), || {
+        state.metrics.record_calculation();
+        compute(op, a, b)
+    })
+}
+
+// TypeScript equivalent:
+// interface BatchResult { result?: number; error?: string; }
+#[derive(Serialize,Deserialize)]
+pub struct BatchResult {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+pub const MAX_BATCH_SIZE: usize = 10_000;
+
+// POST /calculate/batch - evaluate many requests at once
+// Results come back in request order; each entry succeeds or fails on its own
+//
+// TypeScript equivalent:
+// async function calculateBatch(reqs: CalculatorRequest[]): Promise<BatchResult[]>
+pub async fn calculate_batch(
+    State(state): State<Arc<AppState>>,
+    body: Result<Json<Vec<CalculatorRequest>>, JsonRejection>,
+) -> Result<Json<Vec<BatchResult>>, (StatusCode, Json<ErrorResponse>)> {
+    let Json(requests) = body.map_err(|rejection| bad_request(rejection.body_text()))?;
+
+    if requests.len() > MAX_BATCH_SIZE {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: format!("Batch too large: at most {} entries", MAX_BATCH_SIZE),
+            }),
+        ));
+    }
+
+    let results = requests
+        .iter()
+        .map(|params| {
+            let op = params.op.operation;
+            let outcome = if operation_allowed(&state.config, op) {
+                params
+                    .operands()
+                    .and_then(|(a, b)| cached_compute(&state, op, a, b))
+            } else {
+                Err("operation disabled".to_string())
+            };
+            match outcome {
+                Ok(result) => BatchResult { result: Some(result), error: None },
+                Err(error) => BatchResult { result: None, error: Some(error) },
+            }
+        })
+        .collect();
+
+    Ok(Json(results))
+}
+
+// Operation allowlist for locked-down deployments
+// CALC_ALLOWED_OPS=add,subtract only allows those two; unset (or empty) allows everything
+//
+// TypeScript equivalent:
+// const isAllowed = (op: Operation) => !config.calcAllowedOps || config.calcAllowedOps.includes(op);
+fn operation_allowed(config: &Config, op: Operation) -> bool {
+    config
+        .features
+        .calc_allowed_ops
+        .as_ref()
+        .is_none_or(|allowed| allowed.contains(&op))
 }
 // Basic health check endpoint (no database required)
 // TypeScript equivalent:
@@ -100,45 +327,197 @@ pub async fn health() -> Json<serde_json::Value> {
 }
 
 // Database health check endpoint
+// Reads and writes are checked separately: a read replica can answer SELECT 1
+// while writes to the primary are failing
+// { "status": "degraded", "read": "ok", "write": "degraded" } with 503 if either fails
+//
 // TypeScript equivalent:
 // async function dbHealth(pool: Pool) {
-//   try {
-//     await pool.query('SELECT 1');
-//     return { status: "ok" };
-//   } catch(err) {
-//     return { status: 503, error: "Database unavailable" };
-//   }
+//   const read = await pool.query('SELECT 1').then(() => 'ok', () => 'degraded');
+//   const write = await writeCheck(pool).then(() => 'ok', () => 'degraded');
+//   res.status(read === 'ok' && write === 'ok' ? 200 : 503).json({ read, write });
 // }
-pub async fn db_health(State(pool): State<PgPool>) -> Result<Json<serde_json::Value>, StatusCode> {
-    match crate::db::health_check(&pool).await {
-        Ok(_) => Ok(Json(serde_json::json!({
-            "status": "ok",
-            "message": "Database connection is healthy"
-        }))),
-        Err(_) => Err(StatusCode::SERVICE_UNAVAILABLE),
+pub async fn db_health(
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    // Reused for DB_HEALTH_CACHE_MS while healthy, so frequent probes don't each hit the DB
+    let health = state
+        .cache
+        .db_health
+        .get_or_check(|| async {
+            state.metrics.record_db_health_check();
+            let read = db::health_check(&state.pool, state.metrics.acquire_waits()).await;
+            let write = db::write_check(&state.pool, state.metrics.acquire_waits()).await;
+
+            if let Err(error) = &write {
+                tracing::warn!("Database write check failed: {}", error);
+            }
+
+            db::DbHealth {
+                read: read.is_ok(),
+                write: write.is_ok(),
+            }
+        })
+        .await;
+
+    let label = |ok: bool| if ok { "ok" } else { "degraded" };
+    let healthy = health.is_healthy();
+
+    let status = if healthy { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+
+    (
+        status,
+        Json(serde_json::json!({
+            "status": label(healthy),
+            "read": label(health.read),
+            "write": label(health.write)
+        })),
+    )
+}
+
+// Backpressure: past `max`, requests wait here before reaching a handler
+// Each router gets its own semaphore, so a saturated /users can't starve /calculate
+// - /calculate*: CALC_MAX_CONCURRENT_REQUESTS
+// - /users*:     USERS_MAX_CONCURRENT_REQUESTS, falling back to MAX_CONCURRENT_REQUESTS
+fn with_concurrency_limit(router: Router, max: Option<usize>) -> Router {
+    match max {
+        Some(max) => router.layer(GlobalConcurrencyLimitLayer::new(max)),
+        None => router,
     }
 }
-pub fn create_app(pool: PgPool) -> Router {
-    Router::new()
+
+// Per-key / per-IP quotas (RATE_LIMIT_PER_MINUTE, RATE_LIMIT_KEYS; unset = no limit)
+fn with_rate_limit(router: Router, config: Option<&rate_limit::RateLimitConfig>, state: &AppState) -> Router {
+    use rate_limit::{InMemoryRateLimiter, PgRateLimiter, RateLimitBackend, RateLimitState, RateLimiter};
+
+    let Some(config) = config.cloned() else {
+        return router;
+    };
+
+    let limiter: Arc<dyn RateLimiter> = match config.backend {
+        RateLimitBackend::Memory => Arc::new(InMemoryRateLimiter::new()),
+        RateLimitBackend::Postgres => Arc::new(PgRateLimiter::new(
+            state.pool.clone(),
+            Arc::clone(state.metrics.acquire_waits()),
+        )),
+    };
+
+    router.layer(axum::middleware::from_fn_with_state(
+        Arc::new(RateLimitState::new(config, limiter)),
+        rate_limit::rate_limit,
+    ))
+}
+
+// A panicking handler answers 500 (INTERNAL) instead of dropping the connection
+pub fn with_panic_recovery(router: Router) -> Router {
+    router.layer(CatchPanicLayer::custom(middleware::panic_response))
+}
+
+// The whole app, with settings from `config` (see config::Config::from_env)
+pub fn create_app(pool: PgPool, config: Config) -> Router {
+    create_app_with_state(AppState::new(pool, config))
+}
+
+// Same, around state built by the caller (tests use it to inspect metrics)
+pub fn create_app_with_state(state: Arc<AppState>) -> Router {
+    let limits = &state.config.limits;
+
+    // CPU-only routes
+    let calculator = calculator_routes(&state);
+
+    // DB-bound routes
+    let users = with_concurrency_limit(
+        Router::new()
+            .route("/users", post(user_handlers::create_user))
+            .route("/users/stream.json", get(user_handlers::stream_users))
+            .route("/users/stats", get(stats_handlers::get_user_stats))
+            .route("/users/signups", get(stats_handlers::get_signups))
+            .route("/users/pagination", get(user_handlers::get_pagination))
+            .route("/users/by-email", get(user_handlers::get_user_by_email))
+            .route("/admin/stats/refresh", get(stats_handlers::refresh_user_stats))
+            .route("/admin/schema", get(admin_handlers::get_schema_status))
+            .route("/users/:id", get(user_handlers::get_user))
+            .route("/users/:id/siblings", get(user_handlers::get_user_siblings))
+            .route("/users/:id/email-change", post(email_change_handlers::start_email_change))
+            .route("/users/email-change/confirm", get(email_change_handlers::confirm_email_change))
+            .route("/users", get(user_handlers::list_users))
+            .route("/users", delete(user_handlers::delete_all_users))
+            .route("/users/:id", put(user_handlers::update_user))
+            .route("/users/:id", delete(user_handlers::delete_user))
+            .with_state(state.clone()),
+        // A users limit around the pool size keeps DB-bound requests from timing out on acquire
+        limits.users_max_concurrent_requests.or(limits.max_concurrent_requests),
+    );
+
+    // Per-key quotas cover the API routes
+    let limited = with_rate_limit(calculator.merge(users), limits.rate_limit.as_ref(), &state);
+
+    // Health checks are never limited, so probes still answer under load
+    let routes = Router::new()
         .route("/health", get(health))
         .route("/health/db", get(db_health))
-        .route("/calculate", get(calculate))
-        .route("/users", post(user_handlers::create_user))
-        .route("/users/:id", get(user_handlers::get_user))
-        .route("/users", get(user_handlers::list_users))
-        .route("/users/:id", put(user_handlers::update_user))
-        .route("/users/:id", delete(user_handlers::delete_user))
-        .with_state(pool)
+        .with_state(state.clone())
+        .merge(limited);
+
+    with_middleware(routes, state)
+}
+
+// /calculate and /calculate/batch, which never touch the database
+fn calculator_routes(state: &Arc<AppState>) -> Router {
+    with_concurrency_limit(
+        Router::new()
+            .route("/calculate", get(calculate))
+            .route("/calculate/batch", post(calculate_batch))
+            .with_state(state.clone()),
+        state.config.limits.calc_max_concurrent_requests,
+    )
+}
+
+// The layers every response goes through, outermost last
+fn with_middleware(routes: Router, state: Arc<AppState>) -> Router {
+    // Innermost, so a panic's 500 is localized and timed like any other error
+    with_panic_recovery(routes)
+        .layer(axum::middleware::from_fn_with_state(state.clone(), middleware::display_timezone))
+        .layer(axum::middleware::from_fn_with_state(state.clone(), middleware::omit_nulls))
+        .layer(axum::middleware::from_fn_with_state(state.clone(), middleware::app_settings))
+        .layer(axum::middleware::from_fn(i18n::localize_errors))
+        .layer(axum::middleware::from_fn_with_state(state.clone(), middleware::pretty_json))
+        .layer(axum::middleware::from_fn(middleware::response_time))
         .layer(
             TraceLayer::new_for_http()
                 .make_span_with(|request: &Request<_>| {
-                    // Create tracing span for each HTTP request
+                    // Create tracing span for each sampled HTTP request
                     // TypeScript equivalent: console.log(`${method} ${uri}`)
-                    info_span!(
-                        "http_request",
-                        method = %request.method(),
-                        uri = %request.uri(),
-                    )
+                    match request.extensions().get::<middleware::TraceSampled>() {
+                        Some(middleware::TraceSampled(false)) => Span::none(),
+                        _ => info_span!(
+                            "http_request",
+                            method = %request.method(),
+                            uri = %request.uri(),
+                        ),
+                    }
                 })
         )
-}
\ No newline at end of file
+        .layer(axum::middleware::from_fn_with_state(state, middleware::sample_traces))
+}
+
+// ============================================================================
+// In-process apps for integration tests
+// ============================================================================
+// Serve one on an ephemeral port instead of relying on an external server:
+//
+//     let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+//     tokio::spawn(axum::serve(listener, test_app_calculator_only()).into_future());
+
+// The whole app around `pool`, with default settings
+pub fn test_app(pool: PgPool) -> Router {
+    create_app(pool, Config::default())
+}
+
+// Only the calculator routes (plus the usual middleware), no database needed
+pub fn test_app_calculator_only() -> Router {
+    // Never connects: no calculator route touches the pool
+    let pool = PgPoolOptions::new().connect_lazy_with(PgConnectOptions::new());
+    let state = AppState::new(pool, Config::default());
+    with_middleware(calculator_routes(&state), state)
+}
@@ -1,8 +1,11 @@
 // Phase 0: Calculator API - A warm-up to learn Rust + Axum basics
 // Phase 1: Database Integration - Connect to PostgreSQL with SQLx
 
-use std::net::SocketAddr;
+use rust_api_crud::config::Config;
 use rust_api_crud::create_app;
+use rust_api_crud::db::{create_pool_with_config, wait_for_min_connections};
+use rust_api_crud::server::{serve, serve_tls};
+use rust_api_crud::startup::log_effective_config;
 
 // Main function - async like TypeScript async function
 // TypeScript equivalent:
@@ -13,28 +16,54 @@ async fn main() {
 
     // Initialize tracing (like console.log but better)
     tracing_subscriber::fmt::init();
-    
-    let database_url = std::env::var("DATABASE_URL")
-        .expect("DATABASE_URL must be set in .env file");
-    
+
+    // Read and validate every setting once; report all problems, not just the first
+    let config = match Config::from_env() {
+        Ok(config) => config,
+        Err(errors) => {
+            for error in &errors.0 {
+                tracing::error!("❌ Invalid configuration: {}", error);
+            }
+            std::process::exit(1);
+        }
+    };
+
+    // What this process actually resolved, with secrets masked; logged before
+    // anything can fail on it (e.g. a database that can't be reached)
+    log_effective_config(&config);
+
     tracing::info!("📊 Connecting to database...");
 
-    let pool = rust_api_crud::db::create_pool(&database_url)
+    let pool_config = config.database.pool.clone();
+    let pool = create_pool_with_config(&config.database.url, &pool_config)
         .await
         .expect("Failed to create database pool");
 
     tracing::info!("✅ Database pool created");
 
+    // The pool opens min_connections in the background; give it a moment, then check
+    wait_for_min_connections(&pool, pool_config.min_connections).await;
+
+    // Migrations are embedded in the binary, no ./migrations directory needed
+    rust_api_crud::db::run_embedded_migrations(&pool)
+        .await
+        .expect("Failed to run database migrations");
+
+    tracing::info!("✅ Migrations applied");
+
+    // HTTPS when TLS_CERT_PATH and TLS_KEY_PATH are both set, plain HTTP otherwise
+    let addr = config.server.bind_addr;
+    let tls = config.server.tls.clone();
+    let scheme = if tls.is_some() { "https" } else { "http" };
+    let settings = config.server.settings;
+
     // Build the router - like Express app routing
     // TypeScript equivalent:
     // const app = express();
     // app.get('/calculate', calculate);
-    let app = create_app(pool);
-
-    // Set the address
-    let addr = SocketAddr::from(([0, 0, 0, 0], 3000));
+    let app = create_app(pool, config);
 
-    tracing::info!("🚀 User CRUD API listening on http://{}", addr);
+    tracing::info!("🚀 User CRUD API listening on {}://{}", scheme, addr);
     // tracing::info!("📝 Try: http://localhost:3000/calculate?a=5&b=3&op=add");
 
     // Start the server
@@ -44,9 +73,13 @@ async fn main() {
         .await
         .unwrap();
 
-    axum::serve(listener, app)
-        .await
-        .unwrap();
+    // Connection-level timeouts guard against slow-header (slowloris) clients
+    match tls {
+        Some(tls) => serve_tls(listener, app, settings, &tls)
+            .await
+            .expect("Failed to serve HTTPS (check TLS_CERT_PATH / TLS_KEY_PATH)"),
+        None => serve(listener, app, settings).await,
+    }
 }
 
 // 🎓 Learning Notes:
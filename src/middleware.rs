@@ -0,0 +1,195 @@
+// Middleware module - Cross-cutting request/response processing
+//
+// Axum middleware are async functions that receive the request and a `Next`
+// to call the rest of the stack.
+//
+// TypeScript equivalent (Express):
+// app.use(async (req, res, next) => { ...; await next(); ... });
+
+use std::any::Any;
+use std::sync::Arc;
+use std::time::Instant;
+
+use axum::{
+    body::{self, Body, HttpBody},
+    extract::{Request, State},
+    http::{header, HeaderValue},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::db::with_acquire_wait_warn;
+use crate::error::ApiError;
+use crate::models::nulls::with_omit_nulls;
+use crate::models::response::with_json_case;
+use crate::models::timestamp::{parse_timezone, with_display_timezone, with_timestamp_format};
+use crate::state::AppState;
+
+// Adds X-Response-Time-Ms: time from receiving the request until the
+// response is ready (handler + DB time), in milliseconds
+// Streamed bodies are measured up to the first byte, not the last
+pub async fn response_time(request: Request, next: Next) -> Response {
+    let start = Instant::now();
+    let mut response = next.run(request).await;
+
+    let elapsed_ms = start.elapsed().as_secs_f64() * 1000.0;
+    if let Ok(value) = HeaderValue::from_str(&format!("{:.3}", elapsed_ms)) {
+        response.headers_mut().insert(X_RESPONSE_TIME_MS, value);
+    }
+
+    response
+}
+
+pub const X_RESPONSE_TIME_MS: &str = "x-response-time-ms";
+
+// Whether this request gets the detailed http_request span (see sample_traces)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraceSampled(pub bool);
+
+// Trace only TRACE_SAMPLE_RATE of requests. The decision is made up front and
+// stored on the request for TraceLayer's make_span_with; an unsampled request
+// that ends in 4xx/5xx has no span, so it is logged here instead.
+pub async fn sample_traces(
+    State(state): State<Arc<AppState>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let rate = state.config.features.trace_sample_rate;
+    let sampled = rate >= 1.0 || rand::random::<f64>() < rate;
+    request.extensions_mut().insert(TraceSampled(sampled));
+
+    let (method, uri) = (request.method().clone(), request.uri().clone());
+    let response = next.run(request).await;
+    let status = response.status();
+
+    if sampled {
+        state.metrics.record_traced_request();
+    } else if status.is_client_error() || status.is_server_error() {
+        state.metrics.record_traced_request();
+        tracing::warn!(method = %method, uri = %uri, status = status.as_u16(), "unsampled request failed");
+    }
+
+    response
+}
+
+// Bodies larger than this are passed through untouched
+const PRETTY_JSON_MAX_BYTES: usize = 10 * 1024 * 1024;
+
+// Pretty-print JSON responses when ?pretty=true or PRETTY_JSON=true
+// Default output stays compact
+pub async fn pretty_json(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let pretty = query_flag(request.uri().query(), "pretty").unwrap_or(state.config.features.pretty_json);
+
+    let response = next.run(request).await;
+
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"));
+
+    // Streamed bodies have no exact size; buffering them would defeat streaming
+    let is_buffered = response.body().size_hint().exact().is_some();
+
+    if !pretty || !is_json || !is_buffered {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match body::to_bytes(body, PRETTY_JSON_MAX_BYTES).await {
+        Ok(bytes) => bytes,
+        Err(_) => return Response::from_parts(parts, Body::empty()),
+    };
+
+    let pretty_bytes = serde_json::from_slice::<serde_json::Value>(&bytes)
+        .and_then(|value| serde_json::to_vec_pretty(&value))
+        .map(Into::into)
+        .unwrap_or(bytes);
+
+    parts.headers.remove(header::CONTENT_LENGTH);
+    Response::from_parts(parts, Body::from(pretty_bytes))
+}
+
+// Scope this app's settings (JSON_CASE, TIMESTAMP_FORMAT, ACQUIRE_WAIT_WARN_MS)
+// over the request, so code without access to the State (serializers,
+// db::acquire) reads the app's config rather than process-wide state
+pub async fn app_settings(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let config = &state.config;
+    let response = next.run(request);
+    let response = with_json_case(config.features.json_case, response);
+    let response = with_timestamp_format(config.features.timestamp_format, response);
+    with_acquire_wait_warn(config.database.acquire_wait_warn, response).await
+}
+
+// Show RFC3339 timestamps in ?tz=<IANA zone>, else DEFAULT_TZ, else UTC
+// An unknown ?tz= is rejected with 400 (an unknown DEFAULT_TZ is a config error)
+pub async fn display_timezone(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let tz = match query_value(request.uri().query(), "tz") {
+        Some(name) => match parse_timezone(&name) {
+            Some(tz) => tz,
+            None => {
+                return ApiError::BadRequest(format!("Unknown time zone: {}", name)).into_response()
+            }
+        },
+        None => state.config.features.default_tz,
+    };
+
+    with_display_timezone(tz, next.run(request)).await
+}
+
+// Leave null fields out of user responses when ?omit_nulls=true or OMIT_NULLS=true
+// Default output includes them
+pub async fn omit_nulls(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let omit = query_flag(request.uri().query(), "omit_nulls").unwrap_or(state.config.features.omit_nulls);
+
+    with_omit_nulls(omit, next.run(request)).await
+}
+
+// Response for a handler panic (see with_panic_recovery): the usual 500 body.
+// Logged inside the request's span, so the method and URI are on the line.
+// TypeScript equivalent: app.use((err, req, res, next) => res.status(500).json(...))
+pub fn panic_response(panic: Box<dyn Any + Send + 'static>) -> Response {
+    let message = panic
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| panic.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("non-string panic payload");
+    tracing::error!("Handler panicked: {}", message);
+
+    ApiError::Internal.into_response()
+}
+
+// Percent-decoded value of `name` in the query string ("America%2FSao_Paulo")
+fn query_value(query: Option<&str>, name: &str) -> Option<String> {
+    query?
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == name)
+        .and_then(|(_, value)| urlencoding::decode(value).ok())
+        .map(|value| value.into_owned())
+}
+
+// Some(true/false) if `name` is present in the query string, None otherwise
+fn query_flag(query: Option<&str>, name: &str) -> Option<bool> {
+    query?
+        .split('&')
+        .filter_map(|pair| pair.split_once('=').or(Some((pair, ""))))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value == "true" || value == "1" || value.is_empty())
+}
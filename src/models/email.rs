@@ -0,0 +1,38 @@
+// Email canonicalization - one form per mailbox, for duplicate detection
+//
+// The address the user typed is stored as-is; the canonical form only backs
+// the uniqueness check (users.email_canonical).
+//
+// TypeScript equivalent:
+// const canonical = (email: string) => { const [local, domain] = email.toLowerCase().split('@'); ... }
+
+// Providers that ignore dots and +tags in the local part
+const GMAIL_DOMAINS: &[&str] = &["gmail.com", "googlemail.com"];
+
+// Lowercased, and for gmail-family domains without dots or +tags:
+// "John.Doe+news@GoogleMail.com" -> "johndoe@gmail.com"
+// Other domains keep their local part intact, since dots and + can be significant there
+pub fn canonical_email(email: &str) -> String {
+    let email = email.trim().to_lowercase();
+
+    let Some((local, domain)) = email.rsplit_once('@') else {
+        return email;
+    };
+
+    if !GMAIL_DOMAINS.contains(&domain) {
+        return email;
+    }
+
+    let local = local.split('+').next().unwrap_or_default().replace('.', "");
+    format!("{}@gmail.com", local)
+}
+
+// True when the local part, ignoring case and any +tag, is on the reserved list
+// (lowercase entries): "PostMaster+x@example.com" matches "postmaster"
+pub fn is_reserved_email(email: &str, reserved: &[String]) -> bool {
+    let email = email.trim().to_lowercase();
+    let local = email.rsplit_once('@').map_or(email.as_str(), |(local, _)| local);
+    let local = local.split('+').next().unwrap_or_default();
+
+    reserved.iter().any(|part| part == local)
+}
@@ -0,0 +1,114 @@
+// User ids - how new ids are generated and which string forms paths accept
+//
+// Every id is stored as a UUID column. ID_FORMAT only changes how new ids
+// are generated:
+//
+// - uuidv4 (default): random
+// - uuidv7: time-ordered UUID
+// - ulid: 48-bit millisecond timestamp + 80 random bits, the same 128 bits
+//   written as 26 Crockford base32 characters ("01ARZ3NDEKTSV4RRFFQ69G5FAV")
+//
+// Paths accept either string form (the UUID or the ULID) whatever the setting,
+// so ids created under one format keep working after switching.
+//
+// TypeScript equivalent:
+// const id = config.idFormat === 'ulid' ? ulid() : randomUUID();
+
+use serde::{Deserialize, Deserializer};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IdFormat {
+    #[default]
+    UuidV4,
+    UuidV7,
+    Ulid,
+}
+
+impl IdFormat {
+    // "uuidv4" | "uuidv7" | "ulid"
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "uuidv4" => Some(IdFormat::UuidV4),
+            "uuidv7" => Some(IdFormat::UuidV7),
+            "ulid" => Some(IdFormat::Ulid),
+            _ => None,
+        }
+    }
+
+    pub fn generate(self) -> Uuid {
+        match self {
+            IdFormat::UuidV4 => Uuid::new_v4(),
+            IdFormat::UuidV7 => Uuid::now_v7(),
+            IdFormat::Ulid => new_ulid(),
+        }
+    }
+}
+
+// ============================================================================
+// ULID
+// ============================================================================
+
+// Crockford base32: no I, L, O or U
+const CROCKFORD: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+const ULID_LEN: usize = 26;
+
+fn new_ulid() -> Uuid {
+    let millis = chrono::Utc::now().timestamp_millis() as u128;
+    let random = rand::random::<u128>() & ((1 << 80) - 1);
+    Uuid::from_u128((millis << 80) | random)
+}
+
+// 128 bits -> 26 characters, 5 bits each (the first one only carries 3)
+pub fn encode_ulid(id: Uuid) -> String {
+    let value = id.as_u128();
+    (0..ULID_LEN)
+        .rev()
+        .map(|i| CROCKFORD[((value >> (i * 5)) & 0x1f) as usize] as char)
+        .collect()
+}
+
+// Case-insensitive; I and L read as 1, O as 0, as Crockford allows
+// None for anything that isn't 26 valid characters fitting in 128 bits
+pub fn decode_ulid(value: &str) -> Option<Uuid> {
+    if value.len() != ULID_LEN {
+        return None;
+    }
+
+    let mut result: u128 = 0;
+    for (i, c) in value.bytes().enumerate() {
+        let digit = match c.to_ascii_uppercase() {
+            b'I' | b'L' => 1,
+            b'O' => 0,
+            c => CROCKFORD.iter().position(|&d| d == c)? as u128,
+        };
+        // The first character only has room for 3 bits ("7ZZZ..." is the maximum)
+        if i == 0 && digit > 7 {
+            return None;
+        }
+        result = (result << 5) | digit;
+    }
+
+    Some(Uuid::from_u128(result))
+}
+
+// A UUID in any form Uuid understands, or a ULID
+pub fn parse_id(value: &str) -> Option<Uuid> {
+    Uuid::parse_str(value).ok().or_else(|| decode_ulid(value))
+}
+
+// Path parameter for /users/:id routes: accepts the UUID or the ULID string
+//
+//     Path(UserId(id)): Path<UserId>
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UserId(pub Uuid);
+
+impl<'de> Deserialize<'de> for UserId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        parse_id(&value)
+            .map(UserId)
+            .ok_or_else(|| serde::de::Error::custom(format!("invalid user id: {}", value)))
+    }
+}
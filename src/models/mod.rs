@@ -1,6 +1,13 @@
 // Models module - Data structures for the application
 
+pub mod email;
+pub mod id;
+pub mod nulls;
+pub mod response;
+pub mod timestamp;
 pub mod user;
 
 // Re-export for easier imports
+pub use email::{canonical_email, is_reserved_email};
+pub use id::{parse_id, IdFormat, UserId};
 pub use user::*;
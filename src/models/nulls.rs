@@ -0,0 +1,30 @@
+// Null fields - included by default, omitted on request
+//
+// Some strict clients reject explicit nulls ({ "name_updated_at": null }).
+// With ?omit_nulls=true (or OMIT_NULLS=true) nullable user fields are left
+// out of the response instead; see middleware::omit_nulls.
+//
+// TypeScript equivalent:
+// JSON.stringify(user, (key, value) => (omitNulls && value === null ? undefined : value));
+
+use std::future::Future;
+
+// Whether the current request omits nulls, scoped per task like the display zone
+tokio::task_local! {
+    static OMIT_NULLS: bool;
+}
+
+// Run `future` with null fields omitted (or not)
+pub async fn with_omit_nulls<F: Future>(omit: bool, future: F) -> F::Output {
+    OMIT_NULLS.scope(omit, future).await
+}
+
+// The flag set by with_omit_nulls, false outside of one
+pub fn omit_nulls() -> bool {
+    OMIT_NULLS.try_with(|omit| *omit).unwrap_or(false)
+}
+
+// Used via #[serde(skip_serializing_if = "nulls::omitted")]
+pub fn omitted<T>(value: &Option<T>) -> bool {
+    value.is_none() && omit_nulls()
+}
@@ -0,0 +1,332 @@
+// Response DTOs - the JSON shape sent to clients, separate from the DB structs
+//
+// The key case comes from the app's JSON_CASE (see middleware::app_settings):
+// - snake (default): { "created_at": ..., "per_page": ... }
+// - camel:           { "createdAt": ..., "perPage": ... }
+//
+// Handlers wrap their output in `Cased(...)`, which converts the DB struct to
+// its snake_case or camelCase response DTO. The DTOs also carry computed,
+// never-stored fields such as display_name and gravatar_url.
+//
+// TypeScript equivalent:
+// res.json(jsonCase === 'camel' ? camelcaseKeys(user) : user);
+
+use std::collections::BTreeMap;
+use std::future::Future;
+
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Serializer};
+use uuid::Uuid;
+
+use super::{nulls, timestamp};
+use super::{
+    CreatedUserId, PaginationMeta, PendingEmailChange, User, UserListResponse, UserSiblings, UserStats, UserSummary,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsonCase {
+    Snake,
+    Camel,
+}
+
+impl JsonCase {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "snake" => Some(JsonCase::Snake),
+            "camel" => Some(JsonCase::Camel),
+            _ => None,
+        }
+    }
+}
+
+// The app's case for the current request, scoped per task like the display zone
+tokio::task_local! {
+    static JSON_CASE: JsonCase;
+}
+
+// Run `future` with response bodies in `case`
+pub async fn with_json_case<F: Future>(case: JsonCase, future: F) -> F::Output {
+    JSON_CASE.scope(case, future).await
+}
+
+// The case set by with_json_case, snake_case outside of one
+pub fn json_case() -> JsonCase {
+    JSON_CASE.try_with(|case| *case).unwrap_or(JsonCase::Snake)
+}
+
+// A DB type with response DTOs for each JSON case
+pub trait ResponseDto {
+    type Snake: Serialize;
+    type Camel: Serialize;
+
+    fn to_snake(&self) -> Self::Snake;
+    fn to_camel(&self) -> Self::Camel;
+}
+
+// Serializes `T` through its DTO for the configured JSON case
+// Example: Json(Cased(user))
+#[derive(Debug)]
+pub struct Cased<T>(pub T);
+
+impl<T: ResponseDto> Serialize for Cased<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match json_case() {
+            JsonCase::Snake => self.0.to_snake().serialize(serializer),
+            JsonCase::Camel => self.0.to_camel().serialize(serializer),
+        }
+    }
+}
+
+// ============================================================================
+// snake_case DTOs
+// ============================================================================
+// Serialize-only: computed fields are never read from request bodies
+
+// The stored columns, as-is, plus the computed fields
+#[derive(Debug, Serialize)]
+pub struct UserResponse {
+    #[serde(flatten)]
+    pub user: User,
+    pub display_name: String,
+    pub gravatar_url: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UserListResponseDto<T> {
+    pub users: Vec<T>,
+    pub total: i64,
+    pub page: i64,
+    pub per_page: i64,
+    pub total_pages: i64,
+    pub snapshot: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UserSiblingsDto {
+    #[serde(skip_serializing_if = "nulls::omitted")]
+    pub prev: Option<UserResponse>,
+    #[serde(skip_serializing_if = "nulls::omitted")]
+    pub next: Option<UserResponse>,
+}
+
+// ============================================================================
+// camelCase DTOs
+// ============================================================================
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserDto {
+    pub id: Uuid,
+    #[serde(skip_serializing_if = "nulls::omitted")]
+    pub public_id: Option<String>,
+    pub name: String,
+    pub email: String,
+    #[serde(with = "timestamp")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "timestamp")]
+    pub updated_at: DateTime<Utc>,
+    #[serde(with = "timestamp::option", skip_serializing_if = "nulls::omitted")]
+    pub name_updated_at: Option<DateTime<Utc>>,
+    #[serde(with = "timestamp::option", skip_serializing_if = "nulls::omitted")]
+    pub email_updated_at: Option<DateTime<Utc>>,
+    pub display_name: String,
+    pub gravatar_url: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserListDto<T> {
+    pub users: Vec<T>,
+    pub total: i64,
+    pub page: i64,
+    pub per_page: i64,
+    pub total_pages: i64,
+    pub snapshot: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaginationMetaDto {
+    pub total: i64,
+    pub per_page: i64,
+    pub total_pages: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingEmailChangeDto {
+    pub pending_email: String,
+    #[serde(with = "timestamp")]
+    pub expires_at: DateTime<Utc>,
+}
+
+// Same keys in both cases; only the nested users differ
+#[derive(Debug, Serialize)]
+pub struct UserSiblingsCamelDto {
+    #[serde(skip_serializing_if = "nulls::omitted")]
+    pub prev: Option<UserDto>,
+    #[serde(skip_serializing_if = "nulls::omitted")]
+    pub next: Option<UserDto>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserStatsDto {
+    pub total: i64,
+    // Keys are domains, left as-is
+    pub by_domain: BTreeMap<String, i64>,
+    #[serde(with = "timestamp")]
+    pub refreshed_at: DateTime<Utc>,
+}
+
+impl ResponseDto for User {
+    type Snake = UserResponse;
+    type Camel = UserDto;
+
+    fn to_snake(&self) -> UserResponse {
+        UserResponse {
+            user: self.clone(),
+            display_name: self.display_name(),
+            gravatar_url: self.gravatar_url(),
+        }
+    }
+
+    fn to_camel(&self) -> UserDto {
+        UserDto {
+            id: self.id,
+            public_id: self.public_id.clone(),
+            name: self.name.clone(),
+            email: self.email.clone(),
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+            name_updated_at: self.name_updated_at,
+            email_updated_at: self.email_updated_at,
+            display_name: self.display_name(),
+            gravatar_url: self.gravatar_url(),
+        }
+    }
+}
+
+impl<T: ResponseDto> ResponseDto for UserListResponse<T> {
+    type Snake = UserListResponseDto<T::Snake>;
+    type Camel = UserListDto<T::Camel>;
+
+    fn to_snake(&self) -> Self::Snake {
+        UserListResponseDto {
+            users: self.users.iter().map(ResponseDto::to_snake).collect(),
+            total: self.total,
+            page: self.page,
+            per_page: self.per_page,
+            total_pages: self.total_pages,
+            snapshot: self.snapshot,
+        }
+    }
+
+    fn to_camel(&self) -> Self::Camel {
+        UserListDto {
+            users: self.users.iter().map(ResponseDto::to_camel).collect(),
+            total: self.total,
+            page: self.page,
+            per_page: self.per_page,
+            total_pages: self.total_pages,
+            snapshot: self.snapshot,
+        }
+    }
+}
+
+impl ResponseDto for CreatedUserId {
+    type Snake = CreatedUserId;
+    type Camel = CreatedUserId;
+
+    fn to_snake(&self) -> CreatedUserId {
+        self.clone()
+    }
+
+    fn to_camel(&self) -> CreatedUserId {
+        self.clone()
+    }
+}
+
+impl ResponseDto for UserSummary {
+    // id, name and email read the same in both cases
+    type Snake = UserSummary;
+    type Camel = UserSummary;
+
+    fn to_snake(&self) -> UserSummary {
+        self.clone()
+    }
+
+    fn to_camel(&self) -> UserSummary {
+        self.clone()
+    }
+}
+
+impl ResponseDto for PaginationMeta {
+    type Snake = PaginationMeta;
+    type Camel = PaginationMetaDto;
+
+    fn to_snake(&self) -> PaginationMeta {
+        self.clone()
+    }
+
+    fn to_camel(&self) -> PaginationMetaDto {
+        PaginationMetaDto {
+            total: self.total,
+            per_page: self.per_page,
+            total_pages: self.total_pages,
+        }
+    }
+}
+
+impl ResponseDto for PendingEmailChange {
+    type Snake = PendingEmailChange;
+    type Camel = PendingEmailChangeDto;
+
+    fn to_snake(&self) -> PendingEmailChange {
+        self.clone()
+    }
+
+    fn to_camel(&self) -> PendingEmailChangeDto {
+        PendingEmailChangeDto {
+            pending_email: self.pending_email.clone(),
+            expires_at: self.expires_at,
+        }
+    }
+}
+
+impl ResponseDto for UserSiblings {
+    type Snake = UserSiblingsDto;
+    type Camel = UserSiblingsCamelDto;
+
+    fn to_snake(&self) -> UserSiblingsDto {
+        UserSiblingsDto {
+            prev: self.prev.as_ref().map(ResponseDto::to_snake),
+            next: self.next.as_ref().map(ResponseDto::to_snake),
+        }
+    }
+
+    fn to_camel(&self) -> UserSiblingsCamelDto {
+        UserSiblingsCamelDto {
+            prev: self.prev.as_ref().map(ResponseDto::to_camel),
+            next: self.next.as_ref().map(ResponseDto::to_camel),
+        }
+    }
+}
+
+impl ResponseDto for UserStats {
+    // No computed fields: the stats serialize as-is in snake_case
+    type Snake = UserStats;
+    type Camel = UserStatsDto;
+
+    fn to_snake(&self) -> UserStats {
+        self.clone()
+    }
+
+    fn to_camel(&self) -> UserStatsDto {
+        UserStatsDto {
+            total: self.total,
+            by_domain: self.by_domain.clone(),
+            refreshed_at: self.refreshed_at,
+        }
+    }
+}
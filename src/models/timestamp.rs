@@ -0,0 +1,125 @@
+// Timestamp serialization - JSON format for created_at/updated_at
+//
+// The format comes from the app's TIMESTAMP_FORMAT (see middleware::app_settings):
+// - rfc3339 (default): "2024-01-15T10:30:00.123456Z"
+// - epoch_ms:          1705314600123
+//
+// RFC3339 timestamps are shown in UTC unless the request asked for another
+// zone (?tz=America/Sao_Paulo or DEFAULT_TZ, see middleware::display_timezone)
+//
+// TypeScript equivalent:
+// const serializeDate = (d: Date) => format === 'epoch_ms' ? d.getTime() : d.toISOString();
+
+use std::future::Future;
+
+use chrono::{DateTime, SecondsFormat, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimestampFormat {
+    Rfc3339,
+    EpochMs,
+}
+
+impl TimestampFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "rfc3339" => Some(TimestampFormat::Rfc3339),
+            "epoch_ms" => Some(TimestampFormat::EpochMs),
+            _ => None,
+        }
+    }
+}
+
+// The app's format for the current request, scoped per task like the display zone
+tokio::task_local! {
+    static FORMAT: TimestampFormat;
+}
+
+// Run `future` with timestamps serialized in `format`
+pub async fn with_timestamp_format<F: Future>(format: TimestampFormat, future: F) -> F::Output {
+    FORMAT.scope(format, future).await
+}
+
+// The format set by with_timestamp_format, RFC3339 outside of one
+pub fn timestamp_format() -> TimestampFormat {
+    FORMAT.try_with(|format| *format).unwrap_or(TimestampFormat::Rfc3339)
+}
+
+// Display zone for the current request, scoped per task (not process-wide)
+tokio::task_local! {
+    static DISPLAY_TZ: Tz;
+}
+
+// IANA zone name -> Tz ("America/Sao_Paulo", "UTC", ...)
+pub fn parse_timezone(value: &str) -> Option<Tz> {
+    value.parse().ok()
+}
+
+// Run `future` with timestamps displayed in `tz`
+pub async fn with_display_timezone<F: Future>(tz: Tz, future: F) -> F::Output {
+    DISPLAY_TZ.scope(tz, future).await
+}
+
+// The zone set by with_display_timezone, UTC outside of one
+pub fn display_timezone() -> Tz {
+    DISPLAY_TZ.try_with(|tz| *tz).unwrap_or(Tz::UTC)
+}
+
+// Used via #[serde(serialize_with = "timestamp::serialize")]
+pub fn serialize<S: Serializer>(value: &DateTime<Utc>, serializer: S) -> Result<S::Ok, S::Error> {
+    match (timestamp_format(), display_timezone()) {
+        (TimestampFormat::Rfc3339, Tz::UTC) => value.serialize(serializer),
+        // Numeric offset (-03:00), not the zone abbreviation, to stay RFC3339
+        (TimestampFormat::Rfc3339, tz) => serializer.serialize_str(
+            &value.with_timezone(&tz).to_rfc3339_opts(SecondsFormat::AutoSi, true),
+        ),
+        // Epoch milliseconds have no zone
+        (TimestampFormat::EpochMs, _) => serializer.serialize_i64(value.timestamp_millis()),
+    }
+}
+
+// Accepts either representation, so clients (and our tests) can read both formats back
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<DateTime<Utc>, D::Error> {
+    // DateTime<Utc> accepts any offset and converts it back to UTC
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Raw {
+        Rfc3339(DateTime<Utc>),
+        EpochMs(i64),
+    }
+
+    match Raw::deserialize(deserializer)? {
+        Raw::Rfc3339(value) => Ok(value),
+        Raw::EpochMs(millis) => Utc
+            .timestamp_millis_opt(millis)
+            .single()
+            .ok_or_else(|| serde::de::Error::custom("timestamp out of range")),
+    }
+}
+
+// Same formats for nullable timestamps: #[serde(with = "timestamp::option")]
+pub mod option {
+    use chrono::{DateTime, Utc};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        value: &Option<DateTime<Utc>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(value) => super::serialize(value, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<DateTime<Utc>>, D::Error> {
+        #[derive(Deserialize)]
+        struct Wrapper(#[serde(with = "super")] DateTime<Utc>);
+
+        Ok(Option::<Wrapper>::deserialize(deserializer)?.map(|Wrapper(value)| value))
+    }
+}
@@ -1,9 +1,15 @@
 // User model - Database representation and request/response types
 
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
+
+use super::{nulls, timestamp};
+use crate::error::ErrorCode;
+use crate::validation::FieldError;
 
 // Main User struct - matches database table
 // FromRow: Allows SQLx to convert database rows to this struct
@@ -12,10 +18,37 @@ use chrono::{DateTime, Utc};
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct User {
     pub id: Uuid,
+    // Short base62 id for URLs (GET /users/:public_id); the UUID stays canonical
+    #[serde(default, skip_serializing_if = "nulls::omitted")]
+    pub public_id: Option<String>,
     pub name: String,
     pub email: String,
+    #[serde(with = "timestamp")]
     pub created_at: DateTime<Utc>,
+    #[serde(with = "timestamp")]
     pub updated_at: DateTime<Utc>,
+    // Set only when the field actually changes (NULL = never changed)
+    #[serde(default, with = "timestamp::option", skip_serializing_if = "nulls::omitted")]
+    pub name_updated_at: Option<DateTime<Utc>>,
+    #[serde(default, with = "timestamp::option", skip_serializing_if = "nulls::omitted")]
+    pub email_updated_at: Option<DateTime<Utc>>,
+}
+
+// Computed fields - derived on every response, never stored
+impl User {
+    // The trimmed name, or the email's local part when the name is blank
+    pub fn display_name(&self) -> String {
+        match self.name.trim() {
+            "" => self.email.split('@').next().unwrap_or_default().to_string(),
+            name => name.to_string(),
+        }
+    }
+
+    // https://www.gravatar.com/avatar/<md5 of the trimmed, lowercased email>
+    pub fn gravatar_url(&self) -> String {
+        let hash = md5::compute(self.email.trim().to_lowercase());
+        format!("https://www.gravatar.com/avatar/{:x}", hash)
+    }
 }
 
 // Request type for creating a user
@@ -28,25 +61,178 @@ pub struct CreateUserRequest {
 
 // Request type for updating a user
 // All fields optional - allows partial updates
+// The outer Option is "was the key sent", the inner one "was it null":
+//   {}                -> None          (keep)
+//   { "email": null } -> Some(None)    (clear - rejected for non-nullable fields)
+//   { "email": "x" }  -> Some(Some(x)) (set)
+//
+// TypeScript equivalent:
+// interface UpdateUserRequest { name?: string | null; email?: string | null; }
 #[derive(Debug, Deserialize)]
 pub struct UpdateUserRequest {
-    pub name: Option<String>,
-    pub email: Option<String>,
+    #[serde(default, deserialize_with = "present")]
+    pub name: Option<Option<String>>,
+    #[serde(default, deserialize_with = "present")]
+    pub email: Option<Option<String>>,
+}
+
+// Only called for keys that are present, so null becomes Some(None);
+// absent keys fall back to the field's default (None)
+fn present<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: serde::Deserializer<'de>,
+{
+    T::deserialize(deserializer).map(Some)
 }
 
+// Request body for POST /users/:id/email-change
+#[derive(Debug, Deserialize)]
+pub struct EmailChangeRequest {
+    pub email: String,
+}
+
+// Query parameters for GET /users/email-change/confirm
+#[derive(Debug, Deserialize)]
+pub struct EmailChangeConfirmQuery {
+    pub token: Option<String>,
+}
+
+// Response for POST /users/:id/email-change: what's waiting for confirmation
+// The token itself only goes to the new address
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingEmailChange {
+    pub pending_email: String,
+    #[serde(with = "timestamp")]
+    pub expires_at: DateTime<Utc>,
+}
+
+// { "error": "user not found", "code": "USER_NOT_FOUND" }
+// `error` is the human (possibly localized) message; `code` is stable for clients to match on
+// `fields` lists each invalid input for VALIDATION_FAILED, e.g. [{ "field": "page", "message": "..." }]
 #[derive(Serialize,Deserialize)]
 pub struct ErrorResponse {
     pub error: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<ErrorCode>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fields: Option<Vec<FieldError>>,
+}
+
+// Just enough to render a row in a list: GET /users?view=summary
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct UserSummary {
+    pub id: Uuid,
+    pub name: String,
+    pub email: String,
+}
+
+// POST /users?return=minimal: only the new id
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreatedUserId {
+    pub id: Uuid,
+}
+
+// Which shape GET /users returns for each user
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ListView {
+    // The full User (default)
+    #[default]
+    Full,
+    // UserSummary: no timestamps or computed fields
+    Summary,
+}
+
+// ?view=full|summary
+#[derive(Debug, Default, Deserialize)]
+pub struct ViewQuery {
+    #[serde(default)]
+    pub view: ListView,
 }
 
 // Response type for listing users with pagination
+// T is User, or UserSummary for ?view=summary
 #[derive(Debug, Serialize, Deserialize)]
-pub struct UserListResponse {
-    pub users: Vec<User>,
+pub struct UserListResponse<T = User> {
+    pub users: Vec<T>,
     pub total: i64,
     pub page: i64,
     pub per_page: i64,
     pub total_pages: i64,
+    // Pass back as ?snapshot= on later pages to keep a consistent view.
+    // An opaque token: always RFC3339, whatever TIMESTAMP_FORMAT says.
+    pub snapshot: DateTime<Utc>,
+}
+
+// Pager metadata without the rows, for GET /users/pagination
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaginationMeta {
+    pub total: i64,
+    pub per_page: i64,
+    pub total_pages: i64,
+}
+
+// Neighbors of a user in list order, for prev/next navigation
+// Either side is None at the ends of the list
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UserSiblings {
+    pub prev: Option<User>,
+    pub next: Option<User>,
+}
+
+// Aggregate counts behind GET /users/stats (cached, see stats_handlers)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserStats {
+    pub total: i64,
+    // Lowercased email domain -> number of users
+    pub by_domain: BTreeMap<String, i64>,
+    #[serde(with = "timestamp")]
+    pub refreshed_at: DateTime<Utc>,
+}
+
+// Query parameters for GET /users/signups, as sent (validated in the handler)
+#[derive(Debug, Default, Deserialize)]
+pub struct SignupsQuery {
+    // day (default), week or month
+    pub bucket: Option<String>,
+    // Only users created at or after this (same formats as created_after)
+    pub since: Option<String>,
+}
+
+// Width of each GET /users/signups bucket
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignupBucket {
+    Day,
+    Week,
+    Month,
+}
+
+impl SignupBucket {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "day" => Some(SignupBucket::Day),
+            "week" => Some(SignupBucket::Week),
+            "month" => Some(SignupBucket::Month),
+            _ => None,
+        }
+    }
+
+    // The date_trunc field name; only these fixed strings ever reach the query
+    pub fn as_sql(&self) -> &'static str {
+        match self {
+            SignupBucket::Day => "day",
+            SignupBucket::Week => "week",
+            SignupBucket::Month => "month",
+        }
+    }
+}
+
+// One bucket: users created from `date` (a UTC day, Monday or 1st of the month)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignupCount {
+    pub date: NaiveDate,
+    pub count: i64,
 }
 
 // Pagination query parameters
@@ -58,6 +244,28 @@ pub struct Pagination {
     pub per_page: i64,
 }
 
+// What GET /users does with a page past total_pages (OUT_OF_RANGE_PAGE)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutOfRangePage {
+    // An empty users array, echoing the requested page (default)
+    Empty,
+    // The last page instead (the response's `page` says which)
+    Clamp,
+    // 416 Range Not Satisfiable
+    Error,
+}
+
+impl OutOfRangePage {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "empty" => Some(OutOfRangePage::Empty),
+            "clamp" => Some(OutOfRangePage::Clamp),
+            "error" => Some(OutOfRangePage::Error),
+            _ => None,
+        }
+    }
+}
+
 // Default values for pagination
 fn default_page() -> i64 {
     1
@@ -66,3 +274,233 @@ fn default_page() -> i64 {
 fn default_per_page() -> i64 {
     10
 }
+
+// Query parameters for POST /users
+#[derive(Debug, Deserialize)]
+pub struct CreateQuery {
+    // Answer 303 See Other + Location instead of the created user
+    pub redirect: Option<bool>,
+    // ?return=minimal answers with just { "id": ... }
+    #[serde(default, rename = "return")]
+    pub return_preference: ReturnPreference,
+}
+
+// How much of the created row POST /users sends back
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReturnPreference {
+    // The full user (default)
+    #[default]
+    Representation,
+    // Only the id, read with RETURNING id
+    Minimal,
+}
+
+// ?created_after= / ?created_before= exactly as sent, so a malformed value
+// becomes a 400 naming the parameter (see validation::parse_created_range)
+#[derive(Debug, Default, Deserialize)]
+pub struct CreatedRangeQuery {
+    pub created_after: Option<String>,
+    pub created_before: Option<String>,
+}
+
+// Query parameters for GET /users/by-email
+#[derive(Debug, Deserialize)]
+pub struct EmailLookupQuery {
+    pub email: String,
+}
+
+// Query parameters for the token-guarded endpoints (DELETE /users, GET /admin/stats/refresh)
+#[derive(Debug, Deserialize)]
+pub struct ResetQuery {
+    pub confirm: Option<String>,
+}
+
+// Every list filter in one place, deserialized from the query string
+// Example: ?search=alice&created_after=2024-01-01T00:00:00Z&sort=name:asc&snapshot=...
+// Blank values are treated as absent
+//
+// TypeScript equivalent:
+// interface UserFilter { search?: string; lastName?: string; createdAfter?: Date; ... }
+#[derive(Debug, Default, Deserialize)]
+pub struct UserFilter {
+    // Case-insensitive substring match on name or email, trimmed first.
+    // Empty or whitespace-only (?search= from a cleared box) means no filter,
+    // so every user matches. At most 200 characters (validate_search).
+    pub search: Option<String>,
+    // ?last_name=Smith matches "Alice Smith" and "bob smith"
+    pub last_name: Option<String>,
+    // Set by the handler from CreatedRangeQuery, which parses them strictly
+    #[serde(skip)]
+    pub created_after: Option<DateTime<Utc>>,
+    #[serde(skip)]
+    pub created_before: Option<DateTime<Utc>>,
+    // ?sort=name:asc,created_at:desc
+    pub sort: Option<String>,
+    // Direction of the default created_at order when ?sort= isn't given
+    pub order: Option<String>,
+    // Only rows created up to this instant (the snapshot token from the first page)
+    pub snapshot: Option<DateTime<Utc>>,
+    // ?id=...&id=... - only these users. Repeated keys aren't something the
+    // query deserializer handles, so the handler fills this in from the raw query.
+    #[serde(skip)]
+    pub ids: Option<Vec<Uuid>>,
+}
+
+// A value bound to one of the placeholders produced by UserFilter::to_sql_where
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterValue {
+    Text(String),
+    Timestamp(DateTime<Utc>),
+    Ids(Vec<Uuid>),
+}
+
+impl UserFilter {
+    // Build " WHERE ... AND ..." with $1, $2, ... placeholders, plus the values
+    // to bind to them in order. Returns an empty clause when nothing is filtered.
+    // User input only ever ends up in the bind list, never in the SQL text.
+    pub fn to_sql_where(&self) -> (String, Vec<FilterValue>) {
+        let mut conditions = Vec::new();
+        let mut values = Vec::new();
+
+        if let Some(search) = non_blank(&self.search) {
+            values.push(FilterValue::Text(format!("%{}%", escape_like(search))));
+            let n = values.len();
+            conditions.push(format!("(name ILIKE ${n} OR email ILIKE ${n})"));
+        }
+
+        // Last name = the token after the last space in `name`
+        if let Some(last_name) = non_blank(&self.last_name) {
+            values.push(FilterValue::Text(last_name.to_string()));
+            conditions.push(format!(
+                r"lower(regexp_replace(name, '^.*\s', '')) = lower(${})",
+                values.len()
+            ));
+        }
+
+        if let Some(created_after) = self.created_after {
+            values.push(FilterValue::Timestamp(created_after));
+            conditions.push(format!("created_at > ${}", values.len()));
+        }
+
+        if let Some(created_before) = self.created_before {
+            values.push(FilterValue::Timestamp(created_before));
+            conditions.push(format!("created_at < ${}", values.len()));
+        }
+
+        if let Some(snapshot) = self.snapshot {
+            values.push(FilterValue::Timestamp(snapshot));
+            conditions.push(format!("created_at <= ${}", values.len()));
+        }
+
+        if let Some(ids) = &self.ids {
+            values.push(FilterValue::Ids(ids.clone()));
+            conditions.push(format!("id = ANY(${})", values.len()));
+        }
+
+        if conditions.is_empty() {
+            (String::new(), values)
+        } else {
+            (format!(" WHERE {}", conditions.join(" AND ")), values)
+        }
+    }
+}
+
+fn non_blank(value: &Option<String>) -> Option<&str> {
+    value.as_deref().map(str::trim).filter(|value| !value.is_empty())
+}
+
+// Make %, _ and \ match literally inside a LIKE pattern
+fn escape_like(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+// Columns the list endpoint is allowed to sort by
+// Only whitelisted columns ever reach the ORDER BY clause,
+// so user input is never interpolated into SQL directly
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortColumn {
+    Id,
+    Name,
+    Email,
+    CreatedAt,
+    UpdatedAt,
+}
+
+impl SortColumn {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "id" => Some(SortColumn::Id),
+            "name" => Some(SortColumn::Name),
+            "email" => Some(SortColumn::Email),
+            "created_at" => Some(SortColumn::CreatedAt),
+            "updated_at" => Some(SortColumn::UpdatedAt),
+            _ => None,
+        }
+    }
+
+    pub fn as_sql(&self) -> &'static str {
+        match self {
+            SortColumn::Id => "id",
+            SortColumn::Name => "name",
+            SortColumn::Email => "email",
+            SortColumn::CreatedAt => "created_at",
+            SortColumn::UpdatedAt => "updated_at",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortDirection {
+    Asc,
+    Desc,
+}
+
+impl SortDirection {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "asc" => Some(SortDirection::Asc),
+            "desc" => Some(SortDirection::Desc),
+            _ => None,
+        }
+    }
+
+    pub fn as_sql(&self) -> &'static str {
+        match self {
+            SortDirection::Asc => "ASC",
+            SortDirection::Desc => "DESC",
+        }
+    }
+}
+
+// Parse "name:asc,created_at:desc" into validated (column, direction) pairs
+// The direction is optional and defaults to ascending ("name" == "name:asc")
+//
+// TypeScript equivalent:
+// sort.split(',').map(key => { const [col, dir = 'asc'] = key.split(':'); ... })
+pub fn parse_sort(sort: &str) -> Result<Vec<(SortColumn, SortDirection)>, String> {
+    let mut keys = Vec::new();
+
+    for key in sort.split(',').map(str::trim).filter(|key| !key.is_empty()) {
+        let (column, direction) = match key.split_once(':') {
+            Some((column, direction)) => (column, direction),
+            None => (key, "asc"),
+        };
+
+        let column = SortColumn::parse(column)
+            .ok_or_else(|| format!("Unknown sort column: {}", column))?;
+        let direction = SortDirection::parse(direction)
+            .ok_or_else(|| format!("Unknown sort direction: {}", direction))?;
+
+        keys.push((column, direction));
+    }
+
+    Ok(keys)
+}
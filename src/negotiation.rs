@@ -0,0 +1,104 @@
+// Content negotiation - JSON (default) or MessagePack response bodies
+//
+// Handlers take a `ResponseFormat` (picked from the Accept header) and return
+// `Negotiated(format, value)`, which encodes `value` with serde_json or
+// rmp-serde. MessagePack keeps field names, so clients decode it into the
+// same shapes as the JSON responses.
+//
+// TypeScript equivalent:
+// res.format({ 'application/json': () => res.json(v), 'application/msgpack': () => res.send(encode(v)) });
+
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{header, request::Parts, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+use std::convert::Infallible;
+
+pub const APPLICATION_MSGPACK: &str = "application/msgpack";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResponseFormat {
+    Json,
+    MsgPack,
+}
+
+impl ResponseFormat {
+    // Highest-q supported type wins; ties go to the first listed. Anything we
+    // can't serve (or no Accept header) means JSON.
+    // "application/msgpack, application/json;q=0.5" -> MsgPack
+    pub fn from_accept(accept: &str) -> Self {
+        let mut best = (ResponseFormat::Json, -1.0_f32);
+
+        for entry in accept.split(',') {
+            let mut parts = entry.split(';').map(str::trim);
+            let media_type = parts.next().unwrap_or_default().to_ascii_lowercase();
+            let quality = parts
+                .find_map(|param| param.strip_prefix("q="))
+                .and_then(|q| q.parse::<f32>().ok())
+                .unwrap_or(1.0);
+
+            let format = match media_type.as_str() {
+                "application/msgpack" | "application/x-msgpack" => ResponseFormat::MsgPack,
+                "application/json" | "application/*" | "*/*" => ResponseFormat::Json,
+                _ => continue,
+            };
+
+            if quality > 0.0 && quality > best.1 {
+                best = (format, quality);
+            }
+        }
+
+        best.0
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for ResponseFormat {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(parts
+            .headers
+            .get(header::ACCEPT)
+            .and_then(|value| value.to_str().ok())
+            .map(ResponseFormat::from_accept)
+            .unwrap_or(ResponseFormat::Json))
+    }
+}
+
+// A response body encoded in the negotiated format
+#[derive(Debug)]
+pub struct Negotiated<T>(pub ResponseFormat, pub T);
+
+impl<T: Serialize> IntoResponse for Negotiated<T> {
+    fn into_response(self) -> Response {
+        let Negotiated(format, value) = self;
+
+        let encoded = match format {
+            ResponseFormat::Json => serde_json::to_vec(&value)
+                .map(|body| ("application/json", body))
+                .map_err(|error| error.to_string()),
+            ResponseFormat::MsgPack => rmp_serde::to_vec_named(&value)
+                .map(|body| (APPLICATION_MSGPACK, body))
+                .map_err(|error| error.to_string()),
+        };
+
+        match encoded {
+            Ok((content_type, body)) => {
+                let mut response = body.into_response();
+                let headers = response.headers_mut();
+                headers.insert(header::CONTENT_TYPE, HeaderValue::from_static(content_type));
+                // Caches must key on Accept, since the same URL has two encodings
+                headers.insert(header::VARY, HeaderValue::from_static("accept"));
+                response
+            }
+            Err(error) => {
+                tracing::error!("Failed to encode response: {}", error);
+                StatusCode::INTERNAL_SERVER_ERROR.into_response()
+            }
+        }
+    }
+}
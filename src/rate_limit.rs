@@ -0,0 +1,337 @@
+// Rate limiting - fixed-window request quotas per API key (or client IP)
+//
+// Requests with a configured X-API-Key are counted against that key's quota;
+// everything else (no key, or a key not in RATE_LIMIT_KEYS) is counted per
+// client IP at the default quota, so rotating made-up keys doesn't reset the
+// count. Where the counters live is up to a RateLimiter (RATE_LIMIT_BACKEND):
+// - memory:   InMemoryRateLimiter, per process; each replica enforces the full quota
+// - postgres: PgRateLimiter, in the rate_limit_windows table, shared by every replica
+//
+// TypeScript equivalent:
+// app.use(rateLimit({ windowMs: 60_000, keyGenerator: req => req.get('x-api-key') ?? req.ip }));
+
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::{
+    async_trait,
+    extract::{ConnectInfo, Request, State},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use sqlx::PgPool;
+
+use crate::db::{self, AcquireWaits};
+use crate::error::ApiError;
+
+pub const X_API_KEY: &str = "x-api-key";
+
+// Quotas are counted over fixed one-minute windows
+pub const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(60);
+
+// Where counters are kept (RATE_LIMIT_BACKEND=memory|postgres)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RateLimitBackend {
+    // Per process (default)
+    #[default]
+    Memory,
+    // Shared by every instance through the database
+    Postgres,
+}
+
+impl RateLimitBackend {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "memory" => Some(RateLimitBackend::Memory),
+            "postgres" => Some(RateLimitBackend::Postgres),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RateLimitBackend::Memory => "memory",
+            RateLimitBackend::Postgres => "postgres",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RateLimitConfig {
+    // RATE_LIMIT_BACKEND
+    pub backend: RateLimitBackend,
+    // RATE_LIMIT_PER_MINUTE: requests per window for each IP (None = unlimited)
+    pub default_limit: Option<u32>,
+    // RATE_LIMIT_KEYS=partner-a=1000,partner-b=50: the API keys with their
+    // own quota; any other key is limited by IP
+    pub per_key: HashMap<String, u32>,
+}
+
+impl RateLimitConfig {
+    fn limit_for(&self, key: &RateLimitKey) -> Option<u32> {
+        match key {
+            RateLimitKey::ApiKey(api_key) => self.per_key.get(api_key).copied(),
+            RateLimitKey::Ip(_) => self.default_limit,
+        }
+    }
+}
+
+// "partner-a=1000, partner-b=50" -> { partner-a: 1000, partner-b: 50 }
+// Err(the first malformed entry)
+pub(crate) fn parse_key_limits(value: &str) -> Result<HashMap<String, u32>, String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            entry
+                .split_once('=')
+                .and_then(|(key, limit)| Some((key.trim().to_string(), limit.trim().parse().ok()?)))
+                .ok_or_else(|| entry.to_string())
+        })
+        .collect()
+}
+
+// Who a request is counted against
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum RateLimitKey {
+    // Only keys listed in RATE_LIMIT_KEYS
+    ApiKey(String),
+    // None when the server didn't record the peer address
+    Ip(Option<IpAddr>),
+}
+
+impl RateLimitKey {
+    // "key:partner-a", "ip:203.0.113.7", "ip:unknown"
+    fn as_storage_key(&self) -> String {
+        match self {
+            RateLimitKey::ApiKey(api_key) => format!("key:{}", api_key),
+            RateLimitKey::Ip(Some(ip)) => format!("ip:{}", ip),
+            RateLimitKey::Ip(None) => "ip:unknown".to_string(),
+        }
+    }
+}
+
+// Where the per-window counters live
+//
+// TypeScript equivalent:
+// interface RateLimiter { hit(key: string, limit: number): Promise<{ ok: true } | { retryAfter: number }>; }
+#[async_trait]
+pub trait RateLimiter: Send + Sync {
+    // Count one request for `key`; Err(seconds until its window resets) once
+    // `limit` requests were already counted in the current window
+    async fn hit(&self, key: &str, limit: u32) -> Result<(), u64>;
+}
+
+// Round up, so clients never retry a moment too early
+fn retry_after_secs(remaining: Duration) -> u64 {
+    remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0)
+}
+
+// ============================================================================
+// In-memory counters (single instance)
+// ============================================================================
+
+#[derive(Debug)]
+struct Window {
+    started: Instant,
+    count: u32,
+}
+
+#[derive(Debug, Default)]
+struct Windows {
+    by_key: HashMap<String, Window>,
+    // Last sweep for expired windows (None = never)
+    pruned_at: Option<Instant>,
+}
+
+// Counters for this process only; with several replicas each one enforces
+// the full quota on its own
+#[derive(Debug)]
+pub struct InMemoryRateLimiter {
+    window: Duration,
+    windows: Mutex<Windows>,
+}
+
+impl Default for InMemoryRateLimiter {
+    fn default() -> Self {
+        Self::with_window(RATE_LIMIT_WINDOW)
+    }
+}
+
+impl InMemoryRateLimiter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Windows of `window` instead of RATE_LIMIT_WINDOW
+    pub fn with_window(window: Duration) -> Self {
+        Self {
+            window,
+            windows: Mutex::default(),
+        }
+    }
+
+    // Keys with a counter in memory, expired ones not swept yet included
+    pub fn tracked_keys(&self) -> usize {
+        self.windows.lock().unwrap().by_key.len()
+    }
+}
+
+#[async_trait]
+impl RateLimiter for InMemoryRateLimiter {
+    async fn hit(&self, key: &str, limit: u32) -> Result<(), u64> {
+        let now = Instant::now();
+        let mut windows = self.windows.lock().unwrap();
+
+        // Drop expired windows so one-off clients don't accumulate forever;
+        // once per window, not on every request
+        if windows.pruned_at.is_none_or(|at| now.duration_since(at) >= self.window) {
+            windows.by_key.retain(|_, window| now.duration_since(window.started) < self.window);
+            windows.pruned_at = Some(now);
+        }
+
+        let window = windows.by_key.entry(key.to_string()).or_insert(Window {
+            started: now,
+            count: 0,
+        });
+        // Expired but not swept yet: a new window starts
+        if now.duration_since(window.started) >= self.window {
+            *window = Window {
+                started: now,
+                count: 0,
+            };
+        }
+
+        if window.count >= limit {
+            let remaining = self.window.saturating_sub(now.duration_since(window.started));
+            return Err(retry_after_secs(remaining));
+        }
+
+        window.count += 1;
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Postgres counters (shared by every instance)
+// ============================================================================
+
+// Counters in the rate_limit_windows table, so replicas behind a load balancer
+// share one quota. Windows are aligned to the database clock (12:00:00-12:00:59, ...).
+// If the database can't be reached the request is let through: rate limiting
+// shouldn't turn a database blip into a full outage.
+#[derive(Debug, Clone)]
+pub struct PgRateLimiter {
+    pool: PgPool,
+    // The app's acquire wait histogram (AppState::metrics)
+    acquire_waits: Arc<AcquireWaits>,
+}
+
+impl PgRateLimiter {
+    pub fn new(pool: PgPool, acquire_waits: Arc<AcquireWaits>) -> Self {
+        Self { pool, acquire_waits }
+    }
+
+    // (requests counted in the current window including this one, time left in it)
+    async fn increment(&self, key: &str) -> Result<(i32, Duration), sqlx::Error> {
+        let mut conn = db::acquire(&self.pool, &self.acquire_waits).await?;
+        let window_secs = RATE_LIMIT_WINDOW.as_secs_f64();
+
+        // One atomic upsert per request, so concurrent instances never lose a count
+        let row = sqlx::query!(
+            r#"
+            INSERT INTO rate_limit_windows (key, window_start, count)
+            VALUES ($1, to_timestamp(floor(extract(epoch FROM NOW())::FLOAT8 / $2) * $2), 1)
+            ON CONFLICT (key, window_start) DO UPDATE SET count = rate_limit_windows.count + 1
+            RETURNING
+                count,
+                window_start,
+                extract(epoch FROM window_start + make_interval(secs => $2) - NOW())::FLOAT8 AS "remaining_secs!"
+            "#,
+            key,
+            window_secs
+        )
+        .fetch_one(&mut *conn)
+        .await?;
+
+        // First request of a new window: every older window is finished, for
+        // every key, so keys that never come back don't pile up
+        if row.count == 1 {
+            sqlx::query!(
+                "DELETE FROM rate_limit_windows WHERE window_start < $1",
+                row.window_start
+            )
+            .execute(&mut *conn)
+            .await?;
+        }
+
+        Ok((row.count, Duration::from_secs_f64(row.remaining_secs.max(0.0))))
+    }
+}
+
+#[async_trait]
+impl RateLimiter for PgRateLimiter {
+    async fn hit(&self, key: &str, limit: u32) -> Result<(), u64> {
+        match self.increment(key).await {
+            Ok((count, remaining)) if count > i32::try_from(limit).unwrap_or(i32::MAX) => {
+                Err(retry_after_secs(remaining))
+            }
+            Ok(_) => Ok(()),
+            Err(error) => {
+                tracing::warn!("Rate limit check failed, allowing request: {}", error);
+                Ok(())
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Middleware
+// ============================================================================
+
+// Quotas plus the counters they're checked against
+pub struct RateLimitState {
+    config: RateLimitConfig,
+    limiter: Arc<dyn RateLimiter>,
+}
+
+impl RateLimitState {
+    pub fn new(config: RateLimitConfig, limiter: Arc<dyn RateLimiter>) -> Self {
+        Self { config, limiter }
+    }
+}
+
+// Middleware: 429 + Retry-After once the caller's quota for this window is used
+pub async fn rate_limit(
+    State(state): State<Arc<RateLimitState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let key = match request
+        .headers()
+        .get(X_API_KEY)
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| state.config.per_key.contains_key(*value))
+    {
+        Some(api_key) => RateLimitKey::ApiKey(api_key.to_string()),
+        None => RateLimitKey::Ip(
+            request
+                .extensions()
+                .get::<ConnectInfo<SocketAddr>>()
+                .map(|ConnectInfo(addr)| addr.ip()),
+        ),
+    };
+
+    let Some(limit) = state.config.limit_for(&key) else {
+        return next.run(request).await;
+    };
+
+    match state.limiter.hit(&key.as_storage_key(), limit).await {
+        Ok(()) => next.run(request).await,
+        Err(retry_after_secs) => ApiError::RateLimited { retry_after_secs }.into_response(),
+    }
+}
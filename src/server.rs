@@ -0,0 +1,166 @@
+// Server module - HTTP listener with connection-level timeouts
+//
+// axum::serve doesn't expose hyper's connection settings, so connections are
+// accepted here and handed to hyper's builders directly. This lets us cap
+// how long a client may take to send its request headers, and how long a
+// kept-alive connection may sit idle (slowloris defense), and opt in to
+// HTTP/2 (HTTP2=true).
+//
+// TypeScript equivalent (Node):
+// server.headersTimeout = 10_000; server.keepAliveTimeout = 5_000;
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::extract::ConnectInfo;
+use axum::Router;
+use axum_server::tls_rustls::RustlsConfig;
+use hyper::body::Incoming;
+use hyper::server::conn::http1;
+use hyper_util::rt::{TokioExecutor, TokioIo, TokioTimer};
+use hyper_util::server::conn::auto;
+use hyper_util::service::TowerToHyperService;
+use tokio::net::TcpListener;
+use tower::ServiceExt;
+
+// HEADER_READ_TIMEOUT_SECS (default 10), HTTP_KEEP_ALIVE (default true),
+// HTTP_KEEP_ALIVE_TIMEOUT_SECS (default 10) and HTTP2 (default false)
+#[derive(Debug, Clone, Copy)]
+pub struct ServerSettings {
+    // Max time to receive a complete request head
+    pub header_read_timeout: Duration,
+    pub keep_alive: bool,
+    // Max time a connection may wait between requests. On HTTP/1 hyper times
+    // that wait with the header read timer, so the shorter of the two applies
+    // to it. On HTTP/2 the server pings at this interval and closes the
+    // connection if a ping isn't acknowledged within it.
+    pub keep_alive_timeout: Duration,
+    // Also accept HTTP/2: h2c (prior knowledge) on plain TCP, ALPN "h2" over TLS
+    pub http2: bool,
+}
+
+impl Default for ServerSettings {
+    fn default() -> Self {
+        Self {
+            header_read_timeout: Duration::from_secs(10),
+            keep_alive: true,
+            keep_alive_timeout: Duration::from_secs(10),
+            http2: false,
+        }
+    }
+}
+
+impl ServerSettings {
+    // hyper's HTTP/1 header read timer also runs while a connection waits for
+    // its next request, so it doubles as the keep-alive idle timeout
+    fn http1_read_timeout(&self) -> Duration {
+        self.header_read_timeout.min(self.keep_alive_timeout)
+    }
+}
+
+// Accept connections forever, serving each one on its own task
+pub async fn serve(listener: TcpListener, app: Router, settings: ServerSettings) {
+    loop {
+        let (stream, remote_addr) = match listener.accept().await {
+            Ok(connection) => connection,
+            Err(error) => {
+                // e.g. too many open files - back off instead of spinning
+                tracing::error!("Failed to accept connection: {}", error);
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                continue;
+            }
+        };
+
+        // Record the peer address for handlers/middleware (e.g. per-IP rate limits)
+        let service = TowerToHyperService::new(app.clone().map_request(
+            move |mut request: hyper::Request<Incoming>| {
+                request.extensions_mut().insert(ConnectInfo(remote_addr));
+                request
+            },
+        ));
+
+        tokio::spawn(async move {
+            let io = TokioIo::new(stream);
+
+            // The auto builder sniffs the HTTP/2 preface and falls back to HTTP/1.1
+            let result = if settings.http2 {
+                let mut builder = auto::Builder::new(TokioExecutor::new());
+                configure(&mut builder, &settings);
+                builder.serve_connection(io, service).await
+            } else {
+                http1::Builder::new()
+                    .timer(TokioTimer::new())
+                    .header_read_timeout(settings.http1_read_timeout())
+                    .keep_alive(settings.keep_alive)
+                    .serve_connection(io, service)
+                    .await
+                    .map_err(Into::into)
+            };
+
+            if let Err(error) = result {
+                tracing::debug!("Connection from {} closed: {}", remote_addr, error);
+            }
+        });
+    }
+}
+
+// Both halves of an auto (HTTP/1 + HTTP/2) builder
+fn configure(builder: &mut auto::Builder<TokioExecutor>, settings: &ServerSettings) {
+    builder
+        .http1()
+        .timer(TokioTimer::new())
+        .header_read_timeout(settings.http1_read_timeout())
+        .keep_alive(settings.keep_alive);
+    builder
+        .http2()
+        .timer(TokioTimer::new())
+        .keep_alive_interval(settings.keep_alive_timeout)
+        .keep_alive_timeout(settings.keep_alive_timeout);
+}
+
+// ============================================================================
+// HTTPS (optional, for local end-to-end testing)
+// ============================================================================
+
+// Certificate and private key (PEM) for serving HTTPS
+// (TLS_CERT_PATH and TLS_KEY_PATH)
+#[derive(Debug, Clone)]
+pub struct TlsPaths {
+    pub cert: PathBuf,
+    pub key: PathBuf,
+}
+
+// Serve HTTPS with rustls (via axum-server), applying the same connection settings
+// Fails if the certificate or key can't be loaded
+//
+// TypeScript equivalent (Node):
+// https.createServer({ cert, key }, app).listen(3000);
+pub async fn serve_tls(
+    listener: TcpListener,
+    app: Router,
+    settings: ServerSettings,
+    tls: &TlsPaths,
+) -> std::io::Result<()> {
+    let config = RustlsConfig::from_pem_file(&tls.cert, &tls.key).await?;
+
+    // axum-server offers h2 via ALPN by default; only advertise it when enabled
+    let mut tls_config = (*config.get_inner()).clone();
+    tls_config.alpn_protocols = if settings.http2 {
+        vec![b"h2".to_vec(), b"http/1.1".to_vec()]
+    } else {
+        vec![b"http/1.1".to_vec()]
+    };
+    config.reload_from_config(Arc::new(tls_config));
+
+    let mut server = axum_server::from_tcp_rustls(listener.into_std()?, config);
+    let builder = server.http_builder();
+    if !settings.http2 {
+        *builder = builder.clone().http1_only();
+    }
+    configure(builder, &settings);
+
+    server
+        .serve(app.into_make_service_with_connect_info::<std::net::SocketAddr>())
+        .await
+}
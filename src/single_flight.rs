@@ -0,0 +1,79 @@
+// Single flight - identical work that overlaps in time runs once
+//
+// The first caller for a key starts the work; everyone who asks for the same
+// key while it runs awaits that same future and gets a clone of its result.
+// Nothing is remembered afterwards: the entry is removed as soon as the work
+// finishes, so a later call runs it again. Lives in AppState::cache, so every
+// app (and database) has its own in-flight work.
+//
+// TypeScript equivalent:
+// inFlight.get(key) ?? inFlight.set(key, work().finally(() => inFlight.delete(key))).get(key)
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex};
+
+use futures::future::{BoxFuture, FutureExt, Shared};
+
+type InFlight<K, T> = Arc<Mutex<HashMap<K, Shared<BoxFuture<'static, T>>>>>;
+
+pub struct SingleFlight<K, T> {
+    in_flight: InFlight<K, T>,
+}
+
+impl<K, T> Default for SingleFlight<K, T> {
+    fn default() -> Self {
+        Self {
+            in_flight: Default::default(),
+        }
+    }
+}
+
+impl<K, T> std::fmt::Debug for SingleFlight<K, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SingleFlight").finish_non_exhaustive()
+    }
+}
+
+impl<K, T> SingleFlight<K, T>
+where
+    K: Eq + Hash + Clone + Send + 'static,
+    T: Clone + Send + Sync + 'static,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // `work`'s result, or the result of the identical work already in flight
+    // (in which case `work` is dropped without running)
+    pub async fn run<F>(&self, key: K, work: F) -> T
+    where
+        F: Future<Output = T> + Send + 'static,
+    {
+        let flight = {
+            let mut in_flight = self.in_flight.lock().unwrap();
+            match in_flight.get(&key) {
+                Some(flight) => flight.clone(),
+                None => {
+                    let entries = Arc::clone(&self.in_flight);
+                    let flight_key = key.clone();
+                    let flight = async move {
+                        let result = work.await;
+                        entries.lock().unwrap().remove(&flight_key);
+                        result
+                    }
+                    .boxed()
+                    .shared();
+
+                    in_flight.insert(key, flight.clone());
+                    // Runs to completion (and clears its entry) even if every caller disconnects
+                    tokio::spawn(flight.clone());
+                    flight
+                }
+            }
+        };
+
+        flight.await
+    }
+}
@@ -0,0 +1,121 @@
+// App state - everything handlers share, behind one Arc
+//
+// Handlers take `State(state): State<Arc<AppState>>` and reach the pool,
+// settings, counters and caches through it, so adding a shared resource means
+// adding a field here instead of another extractor on every handler.
+//
+// TypeScript equivalent:
+// app.locals = { pool, config, metrics, cache };
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+
+use sqlx::PgPool;
+
+use crate::calc_cache::CalcCache;
+use crate::config::Config;
+use crate::db::{AcquireWaitSnapshot, AcquireWaits, HealthCache};
+use crate::error::ApiError;
+use crate::models::{User, UserStats};
+use crate::handlers::user_handlers::{Created, InFlightCreateKey, UserLookup};
+use crate::single_flight::SingleFlight;
+
+pub struct AppState {
+    pub pool: PgPool,
+    pub config: Config,
+    pub metrics: Metrics,
+    pub cache: Caches,
+}
+
+impl AppState {
+    pub fn new(pool: PgPool, config: Config) -> Arc<Self> {
+        let cache = Caches {
+            db_health: HealthCache::new(config.database.health_cache_ttl),
+            calculator: CalcCache::new(config.features.calc_cache_size, config.features.calc_cache_ttl),
+            user_stats: RwLock::default(),
+            user_creates: SingleFlight::new(),
+            user_reads: SingleFlight::new(),
+        };
+
+        Arc::new(Self {
+            pool,
+            config,
+            metrics: Metrics::default(),
+            cache,
+        })
+    }
+}
+
+// Per-app counters, starting at zero
+#[derive(Debug, Default)]
+pub struct Metrics {
+    // Health checks that actually hit the database (cache misses)
+    db_health_checks: AtomicU64,
+    // Calculations actually computed (calculator cache misses)
+    calculations: AtomicU64,
+    // Requests traced: sampled ones, plus unsampled ones that failed (4xx/5xx)
+    traced_requests: AtomicU64,
+    // GET /users/:id queries actually run (coalesced reads count once)
+    user_reads: AtomicU64,
+    // Time spent waiting for a pooled connection (db::acquire); shared with
+    // the Postgres rate limiter, which checks out connections of its own
+    acquire_waits: Arc<AcquireWaits>,
+}
+
+impl Metrics {
+    pub fn record_db_health_check(&self) {
+        self.db_health_checks.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn db_health_checks(&self) -> u64 {
+        self.db_health_checks.load(Ordering::Relaxed)
+    }
+
+    pub fn record_calculation(&self) {
+        self.calculations.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn calculations(&self) -> u64 {
+        self.calculations.load(Ordering::Relaxed)
+    }
+
+    pub fn record_traced_request(&self) {
+        self.traced_requests.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn traced_requests(&self) -> u64 {
+        self.traced_requests.load(Ordering::Relaxed)
+    }
+
+    pub fn record_user_read(&self) {
+        self.user_reads.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn user_reads(&self) -> u64 {
+        self.user_reads.load(Ordering::Relaxed)
+    }
+
+    // Pass to db::acquire so the wait is recorded here
+    pub fn acquire_waits(&self) -> &Arc<AcquireWaits> {
+        &self.acquire_waits
+    }
+
+    pub fn acquire_wait_snapshot(&self) -> AcquireWaitSnapshot {
+        self.acquire_waits.snapshot()
+    }
+}
+
+// In-process caches
+#[derive(Debug)]
+pub struct Caches {
+    // Last healthy /health/db result (DB_HEALTH_CACHE_MS)
+    pub db_health: HealthCache,
+    // Recent calculator results (CALC_CACHE_SIZE, CALC_CACHE_TTL_SECS)
+    pub calculator: CalcCache,
+    // GET /users/stats counts, filled on first use and by /admin/stats/refresh
+    pub user_stats: RwLock<Option<UserStats>>,
+    // POST /users inserts in progress, shared by identical overlapping creates
+    pub(crate) user_creates: SingleFlight<InFlightCreateKey, Result<Created, ApiError>>,
+    // GET /users/:id lookups in progress, shared by concurrent reads of the same id
+    pub(crate) user_reads: SingleFlight<UserLookup, Result<Option<User>, ApiError>>,
+}
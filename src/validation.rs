@@ -0,0 +1,312 @@
+// Validation module - field-level checks on request input
+//
+// Validators return every problem at once as a list of FieldError, so a client
+// can highlight all invalid fields in one round trip.
+//
+// TypeScript equivalent:
+// type FieldError = { field: string; message: string };
+// function validatePassword(pw: string): FieldError[] { ... }
+
+use axum::{
+    async_trait,
+    extract::{FromRequest, Request},
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::error::ApiError;
+use crate::models::{
+    CreateUserRequest, CreatedRangeQuery, EmailChangeRequest, Pagination, UpdateUserRequest,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: &str, message: impl Into<String>) -> Self {
+        Self {
+            field: field.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+// ============================================================================
+// Validated JSON bodies
+// ============================================================================
+// Handlers take `ValidatedJson<T>` instead of `Json<T>` and get a body that
+// already passed `T::validate`. Malformed JSON is rejected the way `Json`
+// rejects it; a body that parses but breaks a rule gets a 422
+// VALIDATION_FAILED with every FieldError.
+//
+// TypeScript equivalent:
+// app.post('/users', validateBody(createUserSchema), handler);
+
+// A request body that checks its own fields
+pub trait Validate {
+    fn validate(&self) -> Result<(), Vec<FieldError>>;
+}
+
+#[derive(Debug)]
+pub struct ValidatedJson<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for ValidatedJson<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(value) = Json::<T>::from_request(request, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+
+        value
+            .validate()
+            .map_err(|fields| ApiError::InvalidBody(fields).into_response())?;
+
+        Ok(Self(value))
+    }
+}
+
+// Matches the users.name and users.email columns (VARCHAR(255))
+pub const MAX_FIELD_CHARS: usize = 255;
+
+impl Validate for CreateUserRequest {
+    // Blank names are allowed (display_name falls back to the email)
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = Vec::new();
+
+        if self.name.chars().count() > MAX_FIELD_CHARS {
+            errors.push(FieldError::new(
+                "name",
+                format!("name must be at most {} characters", MAX_FIELD_CHARS),
+            ));
+        }
+        errors.extend(validate_email(&self.email));
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+impl Validate for UpdateUserRequest {
+    // Only the fields that are present; an explicit null is the handler's call
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = Vec::new();
+
+        if let Some(Some(name)) = &self.name {
+            if name.chars().count() > MAX_FIELD_CHARS {
+                errors.push(FieldError::new(
+                    "name",
+                    format!("name must be at most {} characters", MAX_FIELD_CHARS),
+                ));
+            }
+        }
+        if let Some(Some(email)) = &self.email {
+            errors.extend(validate_email(email));
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+impl Validate for EmailChangeRequest {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        validate_email(&self.email).map_or(Ok(()), |error| Err(vec![error]))
+    }
+}
+
+// "local@domain", both parts non-empty, no spaces; surrounding whitespace is ignored
+// Deliverability is the mail server's call, not ours
+fn validate_email(email: &str) -> Option<FieldError> {
+    let email = email.trim();
+
+    let message = if email.is_empty() {
+        "email cannot be empty".to_string()
+    } else if email.chars().count() > MAX_FIELD_CHARS {
+        format!("email must be at most {} characters", MAX_FIELD_CHARS)
+    } else {
+        let well_formed = email.split_once('@').is_some_and(|(local, domain)| {
+            !local.is_empty() && !domain.is_empty() && !domain.contains('@')
+        }) && !email.contains(char::is_whitespace);
+        if well_formed {
+            return None;
+        }
+        format!("email must look like name@example.com, got '{}'", email)
+    };
+
+    Some(FieldError::new("email", message))
+}
+
+// ============================================================================
+// Password policy
+// ============================================================================
+// For user creation and the change-password endpoint, once users have passwords.
+// Configured by PASSWORD_MIN_LENGTH, PASSWORD_REQUIRE_DIGIT and
+// PASSWORD_REQUIRE_LETTER (config.features.password_policy).
+
+// Default: at least 8 characters, with a digit and a letter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PasswordPolicy {
+    pub min_length: usize,
+    pub require_digit: bool,
+    pub require_letter: bool,
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        Self {
+            min_length: 8,
+            require_digit: true,
+            require_letter: true,
+        }
+    }
+}
+
+// Check `password` against the policy, collecting every rule it breaks
+// Length is counted in characters, not bytes, so "ñ" counts once
+pub fn validate_password(password: &str, policy: &PasswordPolicy) -> Result<(), Vec<FieldError>> {
+    let mut errors = Vec::new();
+
+    if password.chars().count() < policy.min_length {
+        errors.push(FieldError::new(
+            "password",
+            format!("must be at least {} characters", policy.min_length),
+        ));
+    }
+
+    if policy.require_digit && !password.chars().any(|c| c.is_ascii_digit()) {
+        errors.push(FieldError::new("password", "must contain at least one digit"));
+    }
+
+    if policy.require_letter && !password.chars().any(char::is_alphabetic) {
+        errors.push(FieldError::new("password", "must contain at least one letter"));
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+// ============================================================================
+// Pagination
+// ============================================================================
+// For GET /users and GET /users/pagination, after the query string is parsed
+
+pub const MAX_PER_PAGE: i64 = 100;
+
+// One error per bad parameter, so both can be reported together
+pub fn validate_pagination(pagination: &Pagination) -> Result<(), Vec<FieldError>> {
+    let mut errors = Vec::new();
+
+    if pagination.page < 1 {
+        errors.push(FieldError::new("page", "page must be >= 1"));
+    }
+
+    if !(1..=MAX_PER_PAGE).contains(&pagination.per_page) {
+        errors.push(FieldError::new(
+            "per_page",
+            format!("per_page must be between 1 and {}", MAX_PER_PAGE),
+        ));
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+// ============================================================================
+// Search
+// ============================================================================
+// For ?search= on GET /users and GET /users/pagination
+
+pub const MAX_SEARCH_CHARS: usize = 200;
+
+// Counted after trimming, in characters; a blank term is "no filter", not an error
+pub fn validate_search(search: Option<&str>) -> Result<(), Vec<FieldError>> {
+    match search {
+        Some(search) if search.trim().chars().count() > MAX_SEARCH_CHARS => Err(vec![FieldError::new(
+            "search",
+            format!("search must be at most {} characters", MAX_SEARCH_CHARS),
+        )]),
+        _ => Ok(()),
+    }
+}
+
+// ============================================================================
+// Created-at range
+// ============================================================================
+// For ?created_after= and ?created_before= on GET /users and GET /users/pagination
+
+// A full RFC3339 timestamp ("2024-01-31T12:00:00Z"), or a bare date
+// ("2024-01-31") meaning the start of that day in UTC. Anything else
+// ("2024-01", a timestamp without an offset) is an error naming the parameter.
+pub fn parse_timestamp_param(field: &str, value: &str) -> Result<DateTime<Utc>, FieldError> {
+    let value = value.trim();
+
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(value) {
+        return Ok(timestamp.with_timezone(&Utc));
+    }
+
+    // Exactly YYYY-MM-DD; chrono alone would also take "2024-1-5"
+    let is_date_shaped = value.len() == 10
+        && value.bytes().enumerate().all(|(i, b)| match i {
+            4 | 7 => b == b'-',
+            _ => b.is_ascii_digit(),
+        });
+    if is_date_shaped {
+        if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+            return Ok(date.and_time(NaiveTime::MIN).and_utc());
+        }
+    }
+
+    Err(FieldError::new(
+        field,
+        format!(
+            "{} must be an RFC3339 timestamp (2024-01-31T12:00:00Z) or a date (2024-01-31), got '{}'",
+            field, value
+        ),
+    ))
+}
+
+// (created_after, created_before)
+pub type CreatedRange = (Option<DateTime<Utc>>, Option<DateTime<Utc>>);
+
+// Both bounds, each None when absent or blank; both reported if both are bad
+pub fn parse_created_range(range: &CreatedRangeQuery) -> Result<CreatedRange, Vec<FieldError>> {
+    let mut errors = Vec::new();
+    let mut parse = |field: &str, value: &Option<String>| {
+        let value = value.as_deref().filter(|value| !value.trim().is_empty())?;
+        parse_timestamp_param(field, value)
+            .map_err(|error| errors.push(error))
+            .ok()
+    };
+
+    let created_after = parse("created_after", &range.created_after);
+    let created_before = parse("created_before", &range.created_before);
+
+    if errors.is_empty() {
+        Ok((created_after, created_before))
+    } else {
+        Err(errors)
+    }
+}
@@ -0,0 +1,65 @@
+// App state tests - the app built around a caller-owned AppState
+
+mod common;
+
+use std::time::Duration;
+
+use rust_api_crud::create_app_with_state;
+use rust_api_crud::models::User;
+use rust_api_crud::state::AppState;
+use serde_json::json;
+use uuid::Uuid;
+
+#[tokio::test]
+async fn test_crud_through_shared_state() {
+    let Some(pool) = common::try_setup_test_db().await else { return };
+    let Some(mut config) = common::try_test_config() else { return };
+    config.database.health_cache_ttl = Duration::from_secs(60);
+    let state = AppState::new(pool, config);
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://127.0.0.1:{}", listener.local_addr().unwrap().port());
+    let app = create_app_with_state(state.clone());
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    let client = reqwest::Client::new();
+
+    // Create, read, update, delete
+    let response = client
+        .post(format!("{}/users", url))
+        .json(&json!({ "name": "State", "email": format!("state-{}@example.com", Uuid::new_v4()) }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 201);
+    let user: User = response.json().await.unwrap();
+
+    let fetched: User = client
+        .get(format!("{}/users/{}", url, user.id))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(fetched.id, user.id);
+
+    let response = client
+        .put(format!("{}/users/{}", url, user.id))
+        .json(&json!({ "name": "State renamed" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+
+    let response = client.delete(format!("{}/users/{}", url, user.id)).send().await.unwrap();
+    assert_eq!(response.status(), 204);
+
+    // The health cache and metrics live on the same state: two probes, one check
+    for _ in 0..2 {
+        let response = client.get(format!("{}/health/db", url)).send().await.unwrap();
+        assert_eq!(response.status(), 200);
+    }
+    assert_eq!(state.metrics.db_health_checks(), 1);
+}
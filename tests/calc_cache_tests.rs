@@ -0,0 +1,109 @@
+// Calculator cache tests - identical calculations are computed once
+
+mod common;
+
+use std::cell::Cell;
+use std::time::Duration;
+
+use rust_api_crud::calc_cache::{CalcCache, CalcKey};
+use rust_api_crud::config::Config;
+use rust_api_crud::state::AppState;
+use rust_api_crud::{create_app_with_state, CalculatorResponse, Operation};
+
+#[test]
+fn test_least_recently_used_entry_is_evicted() {
+    let cache = CalcCache::new(2, Duration::from_secs(60));
+    let computed = Cell::new(0);
+    let get = |a: f64| {
+        cache.get_or_compute(CalcKey::new(Operation::Add, a, 1.0), || {
+            computed.set(computed.get() + 1);
+            Ok(a + 1.0)
+        })
+    };
+
+    assert_eq!(get(1.0), Ok(2.0));
+    assert_eq!(get(2.0), Ok(3.0));
+    // Touch 1, so 2 is now the oldest
+    assert_eq!(get(1.0), Ok(2.0));
+    assert_eq!(computed.get(), 2);
+
+    // A third entry evicts 2; 1 is still cached
+    assert_eq!(get(3.0), Ok(4.0));
+    assert_eq!(cache.len(), 2);
+    assert_eq!(get(1.0), Ok(2.0));
+    assert_eq!(computed.get(), 3);
+    assert_eq!(get(2.0), Ok(3.0));
+    assert_eq!(computed.get(), 4);
+}
+
+#[test]
+fn test_expired_and_disabled_caches_recompute() {
+    let key = CalcKey::new(Operation::Divide, 1.0, 0.0);
+    let computed = Cell::new(0);
+    let compute = || {
+        computed.set(computed.get() + 1);
+        Err("Division by zero".to_string())
+    };
+
+    // Errors are cached like results, until the TTL passes
+    let expired = CalcCache::new(10, Duration::ZERO);
+    expired.get_or_compute(key, compute).unwrap_err();
+    expired.get_or_compute(key, compute).unwrap_err();
+    assert_eq!(computed.get(), 2);
+
+    let disabled = CalcCache::new(0, Duration::from_secs(60));
+    disabled.get_or_compute(key, compute).unwrap_err();
+    disabled.get_or_compute(key, compute).unwrap_err();
+    assert_eq!(computed.get(), 4);
+    assert!(disabled.is_empty());
+}
+
+#[test]
+fn test_keys_are_normalized() {
+    assert_ne!(CalcKey::new(Operation::Add, -0.0, 1.0), CalcKey::new(Operation::Add, 0.0, 1.0));
+    // double ignores b
+    assert_eq!(CalcKey::new(Operation::Double, 4.0, 1.0), CalcKey::new(Operation::Double, 4.0, 9.0));
+    assert_ne!(CalcKey::new(Operation::Add, 4.0, 1.0), CalcKey::new(Operation::Subtract, 4.0, 1.0));
+}
+
+#[test]
+fn test_signed_zero_results_are_not_shared() {
+    let cache = CalcCache::new(8, Duration::from_secs(60));
+    let get = |a: f64| {
+        cache
+            .get_or_compute(CalcKey::new(Operation::Multiply, a, 1.0), || {
+                rust_api_crud::compute(Operation::Multiply, a, 1.0)
+            })
+            .unwrap()
+    };
+
+    assert!(get(0.0).is_sign_positive());
+    assert!(get(-0.0).is_sign_negative());
+}
+
+#[tokio::test]
+async fn test_repeated_request_is_served_from_cache() {
+    // The calculator never touches the database, so a lazy pool is enough
+    let pool = common::lazy_pool();
+    let state = AppState::new(pool, Config::default());
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://127.0.0.1:{}", listener.local_addr().unwrap().port());
+    let app = create_app_with_state(state.clone());
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    for op in ["power", "POWER"] {
+        let response = reqwest::get(format!("{}/calculate?a=2&b=10&op={}", url, op))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+        let body: CalculatorResponse = response.json().await.unwrap();
+        assert_eq!(body.result, 1024.0);
+        // The cached result still echoes this request's spelling
+        assert_eq!(body.operation, op);
+    }
+
+    assert_eq!(state.metrics.calculations(), 1);
+}
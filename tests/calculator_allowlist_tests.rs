@@ -0,0 +1,41 @@
+// Calculator allowlist tests - CALC_ALLOWED_OPS restricts the operations
+//
+// The allowlist is part of the app's Config, so each test serves its own
+// app allowing only add and subtract. No database is needed.
+
+mod common;
+
+use rust_api_crud::config::Config;
+use rust_api_crud::{create_app, CalculatorResponse, ErrorResponse, Operation};
+
+async fn server_url() -> String {
+    let mut config = Config::default();
+    config.features.calc_allowed_ops = Some(vec![Operation::Add, Operation::Subtract]);
+    common::serve(create_app(common::lazy_pool(), config)).await
+}
+
+#[tokio::test]
+async fn test_allowed_operation_succeeds() {
+    let url = server_url().await;
+
+    let response = reqwest::get(format!("{}/calculate?a=5&b=3&op=add", url))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+    let data: CalculatorResponse = response.json().await.unwrap();
+    assert_eq!(data.result, 8.0);
+}
+
+#[tokio::test]
+async fn test_disallowed_operation_is_forbidden() {
+    let url = server_url().await;
+
+    let response = reqwest::get(format!("{}/calculate?a=5&b=3&op=multiply", url))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 403);
+    let data: ErrorResponse = response.json().await.unwrap();
+    assert_eq!(data.error, "operation disabled");
+}
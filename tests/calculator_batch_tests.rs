@@ -0,0 +1,52 @@
+// Calculator batch tests - POST /calculate/batch
+
+mod common;
+
+use rust_api_crud::{test_app_calculator_only, BatchResult, ErrorResponse, MAX_BATCH_SIZE};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_batch_entries_succeed_or_fail_independently() {
+    let url = common::serve(test_app_calculator_only()).await;
+
+    let response = reqwest::Client::new()
+        .post(format!("{}/calculate/batch", url))
+        .json(&json!([
+            { "a": 2, "b": 3, "op": "add" },
+            { "a": 1, "b": 0, "op": "divide" },
+            { "a": 10, "b": 4, "op": "subtract" }
+        ]))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+    let results: Vec<BatchResult> = response.json().await.unwrap();
+    assert_eq!(results.len(), 3);
+
+    assert_eq!(results[0].result, Some(5.0));
+    assert_eq!(results[0].error, None);
+
+    assert_eq!(results[1].result, None);
+    assert_eq!(results[1].error.as_deref(), Some("Division by zero"));
+
+    // Order is preserved after a failed entry
+    assert_eq!(results[2].result, Some(6.0));
+}
+
+#[tokio::test]
+async fn test_batch_over_cap_is_rejected() {
+    let url = common::serve(test_app_calculator_only()).await;
+    let batch = vec![json!({ "a": 1, "b": 1, "op": "add" }); MAX_BATCH_SIZE + 1];
+
+    let response = reqwest::Client::new()
+        .post(format!("{}/calculate/batch", url))
+        .json(&batch)
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 400);
+    let body: ErrorResponse = response.json().await.unwrap();
+    assert_eq!(body.error, "Batch too large: at most 10000 entries");
+}
@@ -0,0 +1,106 @@
+// Calculator Operation tests - typed, case-insensitive operations, unknown ones rejected up front
+
+mod common;
+
+use rust_api_crud::{
+    compute, integer_operands, test_app_calculator_only, CalculatorResponse, ErrorResponse, Operation,
+};
+
+#[test]
+fn test_operation_deserializes_from_lowercase_name() {
+    let op: Operation = serde_json::from_str(r#""divide""#).unwrap();
+    assert_eq!(op, Operation::Divide);
+    assert_eq!(op.as_str(), "divide");
+
+    assert!(serde_json::from_str::<Operation>(r#""sqrt""#).is_err());
+    // Matching ignores case; serializing always gives the lowercase name
+    let op: Operation = serde_json::from_str(r#""Add""#).unwrap();
+    assert_eq!(op, Operation::Add);
+    assert_eq!(serde_json::to_string(&op).unwrap(), r#""add""#);
+}
+
+#[test]
+fn test_compute() {
+    assert_eq!(compute(Operation::Power, 2.0, 10.0), Ok(1024.0));
+    assert_eq!(compute(Operation::Double, 21.0, 0.0), Ok(42.0));
+    assert_eq!(compute(Operation::Divide, 1.0, 0.0), Err("Division by zero".to_string()));
+}
+
+#[test]
+fn test_float_and_integer_modulo() {
+    // fmod by default: the remainder keeps the fraction and the sign of a
+    assert_eq!(compute(Operation::Modulo, 10.0, 3.0), Ok(1.0));
+    assert_eq!(compute(Operation::Modulo, 10.5, 3.0), Ok(1.5));
+    assert_eq!(compute(Operation::Modulo, -7.0, 3.0), Ok(-1.0));
+    assert_eq!(compute(Operation::Modulo, 1.0, 0.0), Err("Modulo by zero".to_string()));
+
+    // Integer operands are truncated toward zero
+    assert_eq!(integer_operands(10.5, 3.9), Ok((10.0, 3.0)));
+    assert_eq!(integer_operands(-7.5, 3.0), Ok((-7.0, 3.0)));
+    assert!(integer_operands(1e19, 3.0).is_err());
+    assert!(integer_operands(f64::NAN, 3.0).is_err());
+}
+
+#[tokio::test]
+async fn test_int_query_param_switches_modulo_to_integers() {
+    let url = common::serve(test_app_calculator_only()).await;
+    let modulo = |query: &'static str| {
+        let url = url.clone();
+        async move {
+            let response = reqwest::get(format!("{}/calculate?op=modulo&{}", url, query))
+                .await
+                .unwrap();
+            (response.status(), response.json::<serde_json::Value>().await.unwrap())
+        }
+    };
+
+    let (status, body) = modulo("a=10.5&b=3").await;
+    assert_eq!(status, 200);
+    assert_eq!(body["result"], 1.5);
+
+    let (status, body) = modulo("a=10.5&b=3&int=true").await;
+    assert_eq!(status, 200);
+    assert_eq!(body["result"], 1.0);
+
+    // 0.5 truncates to a zero divisor
+    let (_, body) = modulo("a=10&b=0.5&int=true").await;
+    assert_eq!(body["error"], "Modulo by zero");
+
+    let (status, body) = modulo("a=1e19&b=3&int=true").await;
+    assert_eq!(status, 400);
+    assert!(body["error"].as_str().unwrap().contains("64-bit integer"));
+}
+
+#[tokio::test]
+async fn test_unknown_operation_is_a_json_400() {
+    let url = common::serve(test_app_calculator_only()).await;
+
+    let response = reqwest::get(format!("{}/calculate?a=1&b=2&op=sqrt", url))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 400);
+    let body: ErrorResponse = response.json().await.unwrap();
+    assert!(body.error.contains("unknown variant `sqrt`"), "error was {}", body.error);
+}
+
+#[tokio::test]
+async fn test_operation_matching_ignores_case_and_echoes_the_original() {
+    let url = common::serve(test_app_calculator_only()).await;
+
+    let response = reqwest::get(format!("{}/calculate?a=1&b=2&op=ADD", url))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    let body: CalculatorResponse = response.json().await.unwrap();
+    assert_eq!(body.result, 3.0);
+    assert_eq!(body.operation, "ADD");
+
+    let response = reqwest::get(format!("{}/calculate?a=9&b=3&op=Divide", url))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    let body: CalculatorResponse = response.json().await.unwrap();
+    assert_eq!(body.result, 3.0);
+    assert_eq!(body.operation, "Divide");
+}
@@ -0,0 +1,105 @@
+// Shared helpers for integration tests that serve the app in-process
+//
+// Each #[tokio::test] gets its own runtime, and a server spawned on it dies
+// when that test finishes. So the server runs on a dedicated thread with its
+// own runtime, and every test in the binary talks to it over HTTP.
+//
+// Database-backed tests go through the try_* helpers and return early (skip)
+// on None, so `cargo test` passes without DATABASE_URL. DB-free tests build
+// the app on lazy_pool() with Config::default() and serve it themselves.
+#![allow(dead_code)]
+
+use std::sync::mpsc;
+
+use axum::Router;
+use rust_api_crud::config::Config;
+use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+use sqlx::PgPool;
+
+// DATABASE_URL, or None (with a note on stderr) when it isn't set, so
+// `cargo test` still passes on machines without a database
+pub fn try_database_url() -> Option<String> {
+    dotenv::dotenv().ok();
+    let database_url = std::env::var("DATABASE_URL").ok().filter(|url| !url.is_empty());
+    if database_url.is_none() {
+        eprintln!("DATABASE_URL is not set, skipping database test");
+    }
+    database_url
+}
+
+// Pool for DB-dependent tests, or None when DATABASE_URL isn't set.
+// Tests return early on None:
+//
+//     let Some(pool) = common::try_setup_test_db().await else { return };
+pub async fn try_setup_test_db() -> Option<PgPool> {
+    let database_url = try_database_url()?;
+
+    // A URL that is set but unreachable is a real failure, not a skip
+    Some(
+        rust_api_crud::db::create_pool(&database_url)
+            .await
+            .expect("Failed to create test database pool"),
+    )
+}
+
+// Config from the test environment (.env plus whatever the test set), or
+// None when DATABASE_URL isn't set - the app can't be built without it
+pub fn try_test_config() -> Option<Config> {
+    try_database_url()?;
+    Some(Config::from_env().expect("Invalid test configuration"))
+}
+
+// Pool that never connects, for tests whose requests don't touch the database
+// (calculator routes, connection-level server behavior)
+pub fn lazy_pool() -> PgPool {
+    PgPoolOptions::new().connect_lazy_with(PgConnectOptions::new())
+}
+
+// Serve `router` on an ephemeral port of the current runtime and return its base URL
+// The server stops when the test's runtime does, so it serves only that test
+pub async fn serve(router: Router) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://127.0.0.1:{}", listener.local_addr().unwrap().port());
+    tokio::spawn(async move {
+        axum::serve(listener, router).await.unwrap();
+    });
+    url
+}
+
+// Start `build(pool, config)` on an ephemeral port and return its base URL,
+// or None (skip) without a database. The config is read here, on the
+// caller's thread, before the server starts:
+//
+//     let Some(url) = common::try_spawn_app(create_app) else { return };
+pub fn try_spawn_app<F>(build: F) -> Option<String>
+where
+    F: FnOnce(PgPool, Config) -> Router + Send + 'static,
+{
+    Some(spawn_app_with_config(build, try_test_config()?))
+}
+
+// Same, with a config the test got from try_test_config and tweaked
+pub fn spawn_app_with_config<F>(build: F, config: Config) -> String
+where
+    F: FnOnce(PgPool, Config) -> Router + Send + 'static,
+{
+    let database_url = config.database.url.clone();
+    let (tx, rx) = mpsc::channel();
+
+    std::thread::spawn(move || {
+        let runtime = tokio::runtime::Runtime::new().expect("Failed to build test runtime");
+        runtime.block_on(async move {
+            let pool = rust_api_crud::db::create_pool(&database_url)
+                .await
+                .expect("Failed to create test database pool");
+
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            tx.send(listener.local_addr().unwrap().port()).unwrap();
+
+            axum::serve(listener, build(pool, config)).await.unwrap();
+        });
+    });
+
+    let port = rx.recv().expect("Test server failed to start");
+    format!("http://127.0.0.1:{}", port)
+}
@@ -0,0 +1,56 @@
+// Computed field tests - display_name and gravatar_url on user responses
+
+use chrono::Utc;
+use rust_api_crud::models::response::{Cased, ResponseDto};
+use rust_api_crud::models::User;
+use uuid::Uuid;
+
+fn user(name: &str, email: &str) -> User {
+    let now = Utc::now();
+    User {
+        id: Uuid::new_v4(),
+        public_id: None,
+        name: name.to_string(),
+        email: email.to_string(),
+        created_at: now,
+        updated_at: now,
+        name_updated_at: None,
+        email_updated_at: None,
+    }
+}
+
+#[test]
+fn test_gravatar_url_matches_known_hash() {
+    // Gravatar's documented example: the hash of the trimmed, lowercased email
+    let user = user("Alice", " MyEmailAddress@example.com ");
+
+    assert_eq!(
+        user.gravatar_url(),
+        "https://www.gravatar.com/avatar/0bc83cb571cd1c50ba6f3e8a78ef1346"
+    );
+}
+
+#[test]
+fn test_display_name_falls_back_to_email() {
+    assert_eq!(user("  Alice Smith ", "alice@example.com").display_name(), "Alice Smith");
+    assert_eq!(user("   ", "alice@example.com").display_name(), "alice");
+}
+
+#[test]
+fn test_computed_fields_are_serialized_alongside_columns() {
+    let user = user("Alice", "myemailaddress@example.com");
+
+    let json = serde_json::to_value(user.to_snake()).unwrap();
+
+    assert_eq!(json["name"], "Alice");
+    assert_eq!(json["display_name"], "Alice");
+    assert_eq!(
+        json["gravatar_url"],
+        "https://www.gravatar.com/avatar/0bc83cb571cd1c50ba6f3e8a78ef1346"
+    );
+
+    // The DB struct itself stays column-only
+    let columns = serde_json::to_value(&user).unwrap();
+    assert!(columns.get("gravatar_url").is_none());
+    assert_eq!(serde_json::to_value(Cased(user)).unwrap(), json);
+}
@@ -0,0 +1,73 @@
+// Concurrency limit tests - per-router limits queue excess requests
+//
+// The limit is part of the Config the app is built with, so each test sets
+// it on its own copy.
+
+use std::time::Duration;
+
+use futures::future::join_all;
+use rust_api_crud::create_app;
+use sqlx::postgres::PgPoolOptions;
+
+mod common;
+
+#[tokio::test]
+async fn test_request_spike_is_queued_not_failed() {
+    // Same as the default pool size, so no request ever waits on acquire
+    let Some(mut config) = common::try_test_config() else { return };
+    config.limits.max_concurrent_requests = Some(5);
+    let base_url = common::spawn_app_with_config(create_app, config);
+    let client = reqwest::Client::new();
+
+    // Far more concurrent DB-bound requests than the pool has connections
+    let responses = join_all((0..100).map(|_| {
+        let client = client.clone();
+        let url = format!("{}/users?per_page=5", base_url);
+        async move { client.get(url).send().await.unwrap().status() }
+    }))
+    .await;
+
+    let failures: Vec<_> = responses.iter().filter(|status| !status.is_success()).collect();
+    assert!(failures.is_empty(), "Requests failed under load: {:?}", failures);
+}
+
+#[tokio::test]
+async fn test_saturated_users_routes_do_not_block_calculator() {
+    // One /users request at a time, one DB connection, and the test holds it
+    let Some(mut config) = common::try_test_config() else { return };
+    config.limits.users_max_concurrent_requests = Some(1);
+    let pool = PgPoolOptions::new()
+        .max_connections(1)
+        .acquire_timeout(Duration::from_secs(10))
+        .connect(&config.database.url)
+        .await
+        .expect("Failed to create test database pool");
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://127.0.0.1:{}", listener.local_addr().unwrap().port());
+    let app = create_app(pool.clone(), config);
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let held = pool.acquire().await.unwrap();
+
+    // This takes the only /users permit and then waits for a connection
+    let stuck = tokio::spawn(reqwest::get(format!("{}/users", url)));
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    // The calculator has its own limit, so it still answers immediately
+    let response = tokio::time::timeout(
+        Duration::from_secs(2),
+        reqwest::get(format!("{}/calculate?a=1&b=2&op=add", url)),
+    )
+    .await
+    .expect("/calculate was blocked by the saturated /users routes")
+    .unwrap();
+    assert_eq!(response.status(), 200);
+
+    // Releasing the connection lets the queued /users request finish
+    drop(held);
+    let response = stuck.await.unwrap().unwrap();
+    assert_eq!(response.status(), 200);
+}
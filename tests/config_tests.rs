@@ -0,0 +1,167 @@
+// Config tests - every setting parsed once, every problem reported at once
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use rust_api_crud::config::Config;
+use rust_api_crud::models::{IdFormat, OutOfRangePage, SortDirection};
+use rust_api_crud::validation::PasswordPolicy;
+use rust_api_crud::Operation;
+
+fn vars(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+    pairs
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect()
+}
+
+#[test]
+fn test_defaults_with_only_database_url() {
+    let config = Config::from_vars(&vars(&[("DATABASE_URL", "postgres://localhost/db")])).unwrap();
+
+    assert_eq!(config.database.url, "postgres://localhost/db");
+    assert_eq!(config.server.bind_addr.to_string(), "0.0.0.0:3000");
+    assert!(config.server.tls.is_none());
+    assert!(config.limits.rate_limit.is_none());
+    assert_eq!(config.limits.max_offset, 50_000);
+    assert_eq!(config.features.default_sort_order, SortDirection::Desc);
+    assert_eq!(config.features.calc_allowed_ops, None);
+    assert_eq!(config.features.reserved_email_local_parts, ["postmaster", "abuse", "admin", "root"]);
+    assert_eq!(config.features.password_policy, PasswordPolicy::default());
+}
+
+#[test]
+fn test_parses_a_full_environment() {
+    let config = Config::from_vars(&vars(&[
+        ("DATABASE_URL", "postgres://localhost/db"),
+        ("HOST", "127.0.0.1"),
+        ("PORT", "8080"),
+        ("DB_MIN_CONNECTIONS", "2"),
+        ("DB_APP_NAME", "crud-worker"),
+        ("DB_HEALTH_CACHE_MS", "250"),
+        ("HTTP_KEEP_ALIVE_TIMEOUT_SECS", "30"),
+        ("MAX_CONCURRENT_REQUESTS", "0"),
+        ("CALC_MAX_CONCURRENT_REQUESTS", "8"),
+        ("RATE_LIMIT_KEYS", "partner=3"),
+        ("MAX_USERS", "100"),
+        ("DEFAULT_TZ", "America/Sao_Paulo"),
+        ("DEFAULT_SORT_ORDER", "asc"),
+        ("OUT_OF_RANGE_PAGE", "clamp"),
+        ("ID_FORMAT", "ulid"),
+        ("CALC_ALLOWED_OPS", "add, Subtract"),
+        ("RESERVED_EMAIL_LOCAL_PARTS", "Admin@, root"),
+        ("RESET_TOKEN", "secret"),
+        ("ADMIN_TOKEN", "admin-secret"),
+        ("EMAIL_CHANGE_TTL_SECS", "60"),
+        ("PASSWORD_MIN_LENGTH", "12"),
+        ("PASSWORD_REQUIRE_DIGIT", "false"),
+    ]))
+    .unwrap();
+
+    assert_eq!(config.server.bind_addr.to_string(), "127.0.0.1:8080");
+    assert_eq!(config.database.pool.min_connections, 2);
+    assert_eq!(config.database.pool.application_name, "crud-worker");
+    assert_eq!(config.database.health_cache_ttl, Duration::from_millis(250));
+    assert_eq!(config.server.settings.keep_alive_timeout, Duration::from_secs(30));
+    // 0 means no limit
+    assert_eq!(config.limits.max_concurrent_requests, None);
+    assert_eq!(config.limits.calc_max_concurrent_requests, Some(8));
+    assert_eq!(config.limits.rate_limit.unwrap().per_key["partner"], 3);
+    assert_eq!(config.limits.max_users, Some(100));
+    assert_eq!(config.features.default_tz, chrono_tz::America::Sao_Paulo);
+    assert_eq!(config.features.default_sort_order, SortDirection::Asc);
+    assert_eq!(config.features.out_of_range_page, OutOfRangePage::Clamp);
+    assert_eq!(config.features.id_format, IdFormat::Ulid);
+    assert_eq!(
+        config.features.calc_allowed_ops,
+        Some(vec![Operation::Add, Operation::Subtract])
+    );
+    assert_eq!(config.features.reserved_email_local_parts, ["admin", "root"]);
+    assert_eq!(config.features.reset_token.as_deref(), Some("secret"));
+    assert_eq!(config.features.admin_token.as_deref(), Some("admin-secret"));
+    assert_eq!(config.features.email_change_ttl, Duration::from_secs(60));
+    assert_eq!(
+        config.features.password_policy,
+        PasswordPolicy {
+            min_length: 12,
+            require_digit: false,
+            require_letter: true,
+        }
+    );
+}
+
+#[test]
+fn test_calc_allowed_ops_without_entries_allows_everything() {
+    for value in [",", " , ", ",,"] {
+        let config = Config::from_vars(&vars(&[
+            ("DATABASE_URL", "postgres://localhost/db"),
+            ("CALC_ALLOWED_OPS", value),
+        ]))
+        .unwrap();
+        assert_eq!(config.features.calc_allowed_ops, None, "{:?}", value);
+    }
+}
+
+#[test]
+fn test_reports_every_invalid_variable() {
+    let errors = Config::from_vars(&vars(&[
+        ("PORT", "eighty"),
+        ("DB_MIN_CONNECTIONS", "50"),
+        ("MAX_OFFSET", "-1"),
+        ("MAX_USERS", "0"),
+        ("PRETTY_JSON", "yes"),
+        ("TRACE_SAMPLE_RATE", "1.5"),
+        ("DEFAULT_TZ", "Mars/Olympus"),
+        ("CALC_ALLOWED_OPS", "add,sqrt"),
+        ("TLS_CERT_PATH", "/etc/cert.pem"),
+        ("EMAIL_CHANGE_TTL_SECS", "99999999999"),
+        ("PASSWORD_MIN_LENGTH", "0"),
+        ("PASSWORD_REQUIRE_LETTER", "maybe"),
+    ]))
+    .unwrap_err();
+
+    let vars: Vec<&str> = errors.0.iter().map(|error| error.var.as_str()).collect();
+    assert_eq!(
+        vars,
+        [
+            "DATABASE_URL",
+            "DB_MIN_CONNECTIONS",
+            "PORT",
+            "TLS_KEY_PATH",
+            "MAX_OFFSET",
+            "MAX_USERS",
+            "DEFAULT_TZ",
+            "PRETTY_JSON",
+            "TRACE_SAMPLE_RATE",
+            "CALC_ALLOWED_OPS",
+            "EMAIL_CHANGE_TTL_SECS",
+            "PASSWORD_MIN_LENGTH",
+            "PASSWORD_REQUIRE_LETTER"
+        ]
+    );
+
+    let message = errors.to_string();
+    assert!(message.contains("PRETTY_JSON: expected true or false, got 'yes'"), "{}", message);
+    assert!(message.contains("CALC_ALLOWED_OPS: unknown operation 'sqrt'"), "{}", message);
+    assert!(message.contains("TRACE_SAMPLE_RATE: expected a number from 0.0 to 1.0, got '1.5'"), "{}", message);
+    assert!(message.contains("EMAIL_CHANGE_TTL_SECS: must be at most 31536000 seconds"), "{}", message);
+    // Not clamped to the pool's max_connections (5), reported
+    assert!(message.contains("DB_MIN_CONNECTIONS: must be at most 5"), "{}", message);
+    assert!(message.contains("MAX_OFFSET: must be at least 0"), "{}", message);
+    // 0 would block every create; leave it unset for no limit
+    assert!(message.contains("MAX_USERS: must be at least 1"), "{}", message);
+    assert!(message.contains("PASSWORD_MIN_LENGTH: must be at least 1"), "{}", message);
+}
+
+#[test]
+fn test_empty_values_count_as_unset() {
+    let config = Config::from_vars(&vars(&[
+        ("DATABASE_URL", "postgres://localhost/db"),
+        ("MAX_USERS", ""),
+        ("ID_FORMAT", " "),
+    ]))
+    .unwrap();
+
+    assert_eq!(config.limits.max_users, None);
+    assert_eq!(config.features.id_format, IdFormat::UuidV4);
+}
@@ -12,28 +12,25 @@
 //   it('should connect to database', async () => { ... });
 // });
 
-use sqlx::PgPool;
+use std::str::FromStr;
+use std::sync::Arc;
 
-// Helper function to create a test database pool
-// This is similar to a test fixture in other languages
+use rust_api_crud::config::Config;
+use rust_api_crud::create_app_with_state;
+use rust_api_crud::db::AcquireWaits;
+use rust_api_crud::state::AppState;
+use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+
+mod common;
+
+// Test database pools come from common::try_setup_test_db, which returns None
+// (and the test is skipped) when DATABASE_URL isn't set
 // TypeScript equivalent:
-// async function setupTestDb(): Promise<Pool> {
+// async function setupTestDb(): Promise<Pool | undefined> {
 //   await dotenv.config();
+//   if (!process.env.DATABASE_URL) return undefined; // test.skip
 //   return createPool(process.env.DATABASE_URL);
 // }
-async fn setup_test_db() -> PgPool {
-    // Load environment variables
-    dotenv::dotenv().ok();
-
-    // Get database URL from environment
-    let database_url = std::env::var("DATABASE_URL")
-        .expect("DATABASE_URL must be set for tests");
-
-    // Create a connection pool for testing
-    rust_api_crud::db::create_pool(&database_url)
-        .await
-        .expect("Failed to create test database pool")
-}
 
 // ============================================================================
 // TEST 1: Basic Database Connection
@@ -48,7 +45,7 @@ async fn setup_test_db() -> PgPool {
 #[tokio::test]
 async fn test_database_connection() {
     // Arrange: Set up test database pool
-    let pool = setup_test_db().await;
+    let Some(pool) = common::try_setup_test_db().await else { return };
 
     // Act: Try to execute a simple query
     // SELECT 1 is a standard "ping" query that returns 1 if connection works
@@ -73,10 +70,10 @@ async fn test_database_connection() {
 #[tokio::test]
 async fn test_health_check() {
     // Arrange: Set up test database pool
-    let pool = setup_test_db().await;
+    let Some(pool) = common::try_setup_test_db().await else { return };
 
     // Act: Call our health_check function
-    let result = rust_api_crud::db::health_check(&pool).await;
+    let result = rust_api_crud::db::health_check(&pool, &AcquireWaits::default()).await;
 
     // Assert: Health check should succeed
     assert!(result.is_ok(), "Health check failed: {:?}", result.err());
@@ -91,7 +88,7 @@ async fn test_health_check() {
 #[tokio::test]
 async fn test_pool_statistics() {
     // Arrange: Set up test database pool
-    let pool = setup_test_db().await;
+    let Some(pool) = common::try_setup_test_db().await else { return };
 
     // Act: Get pool statistics
     let (size, idle, max_connections) = rust_api_crud::db::get_database_statistics(&pool);
@@ -117,7 +114,7 @@ async fn test_pool_statistics() {
 #[tokio::test]
 async fn test_run_migrations() {
     // Arrange: Set up test database pool
-    let pool = setup_test_db().await;
+    let Some(pool) = common::try_setup_test_db().await else { return };
 
     // Act: Run migrations
     let result = rust_api_crud::db::run_migrations(&pool).await;
@@ -135,7 +132,7 @@ async fn test_run_migrations() {
 #[tokio::test]
 async fn test_users_table_exists() {
     // Arrange: Set up test database pool
-    let pool = setup_test_db().await;
+    let Some(pool) = common::try_setup_test_db().await else { return };
 
     // Ensure migrations have run
     rust_api_crud::db::run_migrations(&pool)
@@ -166,7 +163,7 @@ async fn test_users_table_exists() {
 #[tokio::test]
 async fn test_pool_connection_reuse() {
     // Arrange: Set up test database pool
-    let pool = setup_test_db().await;
+    let Some(pool) = common::try_setup_test_db().await else { return };
 
     // Get initial idle count
     let (_, idle_before, _) = rust_api_crud::db::get_database_statistics(&pool);
@@ -187,7 +184,7 @@ async fn test_pool_connection_reuse() {
     println!("📊 Idle connections: before={}, after={}", idle_before, idle_after);
 
     // We can't assert exact equality due to timing, but connections should be available
-    assert!(idle_after <= pool.options().get_max_connections() as u32,
+    assert!(idle_after <= pool.options().get_max_connections(),
             "Idle connections should not exceed max");
 }
 
@@ -200,7 +197,7 @@ async fn test_pool_connection_reuse() {
 #[tokio::test]
 async fn test_concurrent_database_queries() {
     // Arrange: Set up test database pool
-    let pool = setup_test_db().await;
+    let Some(pool) = common::try_setup_test_db().await else { return };
 
     // Act: Spawn 10 concurrent queries
     // TypeScript equivalent:
@@ -228,6 +225,378 @@ async fn test_concurrent_database_queries() {
     }
 }
 
+// ============================================================================
+// TEST 8: Embedded Migrations on a Fresh Database
+// ============================================================================
+// This test verifies migrations run from the binary alone
+// A fresh database is created and the working directory is moved somewhere
+// without a ./migrations folder, like a deployed static binary
+
+#[tokio::test]
+async fn test_run_embedded_migrations_without_migrations_dir() {
+    // Arrange: Create a throwaway database
+    let Some(admin_pool) = common::try_setup_test_db().await else { return };
+    let db_name = format!("migrate_test_{}", uuid::Uuid::new_v4().simple());
+    sqlx::query(&format!("CREATE DATABASE {}", db_name))
+        .execute(&admin_pool)
+        .await
+        .expect("Failed to create fresh database");
+
+    let options = PgConnectOptions::from_str(&common::try_database_url().unwrap())
+        .unwrap()
+        .database(&db_name);
+    let fresh_pool = PgPoolOptions::new()
+        .max_connections(1)
+        .connect_with(options)
+        .await
+        .expect("Failed to connect to fresh database");
+
+    // Move to a directory with no migrations in it
+    let original_dir = std::env::current_dir().unwrap();
+    std::env::set_current_dir(std::env::temp_dir()).unwrap();
+    assert!(!std::path::Path::new("migrations").exists());
+
+    // Act: Run the embedded migrations
+    let result = rust_api_crud::db::run_embedded_migrations(&fresh_pool).await;
+
+    std::env::set_current_dir(original_dir).unwrap();
+
+    // Assert: Migrations succeeded and created the users table
+    assert!(result.is_ok(), "Embedded migrations failed: {:?}", result.err());
+
+    let table: Option<String> = sqlx::query_scalar(
+        "SELECT table_name::TEXT FROM information_schema.tables
+         WHERE table_schema = 'public' AND table_name = 'users'"
+    )
+    .fetch_optional(&fresh_pool)
+    .await
+    .unwrap();
+    assert_eq!(table.as_deref(), Some("users"));
+
+    // Cleanup: Drop the throwaway database
+    fresh_pool.close().await;
+    sqlx::query(&format!("DROP DATABASE {}", db_name))
+        .execute(&admin_pool)
+        .await
+        .expect("Failed to drop fresh database");
+}
+
+// ============================================================================
+// TEST 9: Concurrent (No-Transaction) Migrations
+// ============================================================================
+// This test verifies that CONCURRENTLY migrations apply cleanly
+// Running them inside a transaction would fail with
+// "DROP INDEX CONCURRENTLY cannot run inside a transaction block"
+
+#[tokio::test]
+async fn test_concurrent_migrations_apply_outside_transaction() {
+    // Arrange: Set up test database pool
+    let Some(pool) = common::try_setup_test_db().await else { return };
+
+    // Act: Run all migrations twice (the second run must be a no-op)
+    let first = rust_api_crud::db::run_migrations(&pool).await;
+    let second = rust_api_crud::db::run_migrations(&pool).await;
+
+    // Assert: Both runs succeed
+    assert!(first.is_ok(), "Concurrent migrations failed: {:?}", first.err());
+    assert!(second.is_ok(), "Re-running migrations failed: {:?}", second.err());
+
+    // Assert: The unused lower(email) index is gone
+    let index: Option<String> = sqlx::query_scalar("SELECT to_regclass('idx_users_email_lower')::text")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(index, None);
+
+    // Assert: The migration was recorded exactly once
+    let recorded: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM _concurrent_migrations WHERE version = 2"
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(recorded, 1);
+}
+
+// ============================================================================
+// TEST 10: Minimum Warm Connections
+// ============================================================================
+// This test verifies the pool keeps min_connections open after creation
+
+#[tokio::test]
+async fn test_pool_min_connections() {
+    // Arrange: A pool that must keep two warm connections
+    dotenv::dotenv().ok();
+    let Some(database_url) = common::try_database_url() else { return };
+    let config = rust_api_crud::db::PoolConfig {
+        min_connections: 2,
+        ..Default::default()
+    };
+
+    // Act: Create the pool and give it a moment to warm up
+    let pool = rust_api_crud::db::create_pool_with_config(&database_url, &config)
+        .await
+        .expect("Failed to create test database pool");
+    let warmed = rust_api_crud::db::wait_for_min_connections(&pool, 2).await;
+
+    // Assert: At least two idle connections are ready
+    assert!(warmed, "Pool never reached 2 idle connections");
+    assert!(pool.num_idle() >= 2, "Expected >= 2 idle, got {}", pool.num_idle());
+}
+
+// ============================================================================
+// TEST 11: Write Check Leaves Nothing Behind
+// ============================================================================
+// The write check's transaction must be rolled back, so the throwaway table
+// (and its row) never survives on the connection
+
+#[tokio::test]
+async fn test_write_check_rolls_back() {
+    // Arrange: One connection, so the follow-up query runs in the same session
+    dotenv::dotenv().ok();
+    let Some(database_url) = common::try_database_url() else { return };
+    let pool = PgPoolOptions::new()
+        .max_connections(1)
+        .connect(&database_url)
+        .await
+        .expect("Failed to create test database pool");
+
+    // Act
+    let result = rust_api_crud::db::write_check(&pool, &AcquireWaits::default()).await;
+
+    // Assert: The check passed and its temp table was rolled back with the transaction
+    assert!(result.is_ok(), "Write check failed: {:?}", result.err());
+
+    let leftover: Option<String> =
+        sqlx::query_scalar("SELECT to_regclass('pg_temp.health_write_check')::text")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(leftover, None, "health_write_check survived the write check");
+}
+
+// ============================================================================
+// TEST 12: Write Check Fails on a Read-Only Connection
+// ============================================================================
+// Simulates a read replica: SELECT 1 works, writes don't
+
+#[tokio::test]
+async fn test_write_check_fails_when_read_only() {
+    dotenv::dotenv().ok();
+    let Some(database_url) = common::try_database_url() else { return };
+    let options = PgConnectOptions::from_str(&database_url)
+        .unwrap()
+        .options([("default_transaction_read_only", "on")]);
+    let pool = PgPoolOptions::new()
+        .max_connections(1)
+        .connect_with(options)
+        .await
+        .expect("Failed to create read-only pool");
+
+    assert!(rust_api_crud::db::health_check(&pool, &AcquireWaits::default()).await.is_ok());
+    assert!(rust_api_crud::db::write_check(&pool, &AcquireWaits::default()).await.is_err());
+}
+
+// ============================================================================
+// TEST 13: Acquire Wait Is Measured Under Contention
+// ============================================================================
+// With a single connection, concurrent callers have to queue for it
+
+#[tokio::test]
+async fn test_acquire_records_wait_under_contention() {
+    // Arrange: A 1-connection pool and four callers that each hold it for 50ms
+    dotenv::dotenv().ok();
+    let Some(database_url) = common::try_database_url() else { return };
+    let pool = PgPoolOptions::new()
+        .max_connections(1)
+        .connect(&database_url)
+        .await
+        .expect("Failed to create test database pool");
+    let waits = Arc::new(AcquireWaits::default());
+
+    // Act
+    let mut handles = vec![];
+    for _ in 0..4 {
+        let pool = pool.clone();
+        let waits = waits.clone();
+        handles.push(tokio::spawn(async move {
+            let mut conn = rust_api_crud::db::acquire(&pool, &waits).await.unwrap();
+            sqlx::query("SELECT pg_sleep(0.05)").execute(&mut *conn).await.unwrap();
+        }));
+    }
+    for handle in handles {
+        handle.await.unwrap();
+    }
+
+    // Assert: Every acquire was recorded, and the queued ones waited
+    // (the histogram is this test's own, so nothing else is counted in it)
+    let waited = waits.snapshot();
+    assert_eq!(waited.count, 4);
+    assert!(
+        waited.total >= std::time::Duration::from_millis(50),
+        "Expected queued acquires to wait, recorded {:?}",
+        waited.total
+    );
+}
+
+// ============================================================================
+// TEST 14: Test-Before-Acquire Replaces a Dead Connection
+// ============================================================================
+// A connection killed while idle (like after a network blip) is detected by
+// the ping and replaced, so the next query still succeeds
+
+#[tokio::test]
+async fn test_before_acquire_replaces_dead_connection() {
+    // Arrange: A 1-connection pool with the ping enabled, and its backend's pid
+    dotenv::dotenv().ok();
+    let Some(database_url) = common::try_database_url() else { return };
+    let config = rust_api_crud::db::PoolConfig {
+        max_connections: 1,
+        test_before_acquire: true,
+        ..Default::default()
+    };
+    let pool = rust_api_crud::db::create_pool_with_config(&database_url, &config)
+        .await
+        .expect("Failed to create test database pool");
+    let pid: i32 = sqlx::query_scalar("SELECT pg_backend_pid()")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+
+    // Act: Kill that connection from the server side while it sits idle
+    let killer = PgPoolOptions::new()
+        .max_connections(1)
+        .connect(&database_url)
+        .await
+        .expect("Failed to create test database pool");
+    let terminated: bool = sqlx::query_scalar("SELECT pg_terminate_backend($1)")
+        .bind(pid)
+        .fetch_one(&killer)
+        .await
+        .unwrap();
+    assert!(terminated);
+
+    // Assert: Queries keep working, on a fresh connection
+    for _ in 0..3 {
+        let new_pid: i32 = sqlx::query_scalar("SELECT pg_backend_pid()")
+            .fetch_one(&pool)
+            .await
+            .expect("Query failed on a stale connection");
+        assert_ne!(new_pid, pid);
+    }
+}
+
+// ============================================================================
+// TEST 15: Connections Carry the Configured application_name
+// ============================================================================
+// DBAs attribute load in pg_stat_activity by application_name (DB_APP_NAME)
+
+#[tokio::test]
+async fn test_pool_sets_application_name() {
+    // Arrange: The default name, and a custom one
+    dotenv::dotenv().ok();
+    let Some(database_url) = common::try_database_url() else { return };
+
+    for name in [None, Some("crud-test-app")] {
+        let mut config = rust_api_crud::db::PoolConfig::default();
+        if let Some(name) = name {
+            config.application_name = name.to_string();
+        }
+
+        // Act: Ask the server what this session is called
+        let pool = rust_api_crud::db::create_pool_with_config(&database_url, &config)
+            .await
+            .expect("Failed to create test database pool");
+        let current: String = sqlx::query_scalar("SELECT current_setting('application_name')")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+
+        // Assert: The configured name, or the crate name by default
+        assert_eq!(current, name.unwrap_or("rust-api-crud"));
+    }
+}
+
+// ============================================================================
+// TEST 16: Each App Records Its Own Acquire Waits
+// ============================================================================
+// The histogram is part of AppState::metrics, so two apps on one database
+// (or two tests running in parallel) don't see each other's waits
+
+#[tokio::test]
+async fn test_acquire_waits_are_per_app() {
+    // Arrange: Two apps sharing a pool, only the first one serving requests
+    let Some(pool) = common::try_setup_test_db().await else { return };
+    let first = AppState::new(pool.clone(), Config::default());
+    let second = AppState::new(pool, Config::default());
+    let url = common::serve(create_app_with_state(first.clone())).await;
+
+    // Act: /health/db runs a read check and a write check
+    let response = reqwest::get(format!("{}/health/db", url)).await.unwrap();
+    assert_eq!(response.status(), 200);
+
+    // Assert
+    assert_eq!(first.metrics.acquire_wait_snapshot().count, 2);
+    assert_eq!(second.metrics.acquire_wait_snapshot().count, 0);
+}
+
+// ============================================================================
+// TEST 17: Invalid Indexes From a Failed Concurrent Build Are Dropped
+// ============================================================================
+// A failed CREATE INDEX CONCURRENTLY leaves an INVALID index behind, which
+// IF NOT EXISTS would skip forever
+
+async fn index_is_valid(conn: &mut sqlx::PgConnection, index: &str) -> bool {
+    sqlx::query_scalar("SELECT indisvalid FROM pg_index WHERE indexrelid = to_regclass($1)")
+        .bind(index)
+        .fetch_one(conn)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_invalid_index_is_dropped_before_retrying() {
+    // Arrange: A unique index build that fails on duplicate values
+    let Some(pool) = common::try_setup_test_db().await else { return };
+    let suffix = uuid::Uuid::new_v4().simple().to_string();
+    let table = format!("concurrent_index_test_{}", suffix);
+    let index = format!("concurrent_index_test_idx_{}", suffix);
+    let create_index = format!("CREATE UNIQUE INDEX CONCURRENTLY IF NOT EXISTS {} ON {} (value)", index, table);
+    let mut conn = pool.acquire().await.unwrap();
+
+    sqlx::query(&format!("CREATE TABLE {} (value INTEGER)", table))
+        .execute(&mut *conn)
+        .await
+        .unwrap();
+    sqlx::query(&format!("INSERT INTO {} VALUES (1), (1)", table))
+        .execute(&mut *conn)
+        .await
+        .unwrap();
+    assert!(sqlx::query(&create_index).execute(&mut *conn).await.is_err());
+
+    // IF NOT EXISTS now "succeeds" without building anything
+    sqlx::query(&format!("DELETE FROM {} WHERE ctid = (SELECT MIN(ctid) FROM {})", table, table))
+        .execute(&mut *conn)
+        .await
+        .unwrap();
+    sqlx::query(&create_index).execute(&mut *conn).await.unwrap();
+    assert!(!index_is_valid(&mut conn, &index).await);
+
+    // Act: What the runner does before retrying a migration's statement
+    let dropped = rust_api_crud::db::drop_invalid_index(&mut conn, &index).await.unwrap();
+    sqlx::query(&create_index).execute(&mut *conn).await.unwrap();
+
+    // Assert: Rebuilt and valid; a valid index is left alone
+    assert!(dropped);
+    assert!(index_is_valid(&mut conn, &index).await);
+    assert!(!rust_api_crud::db::drop_invalid_index(&mut conn, &index).await.unwrap());
+
+    sqlx::query(&format!("DROP TABLE {}", table))
+        .execute(&mut *conn)
+        .await
+        .unwrap();
+}
+
 // ============================================================================
 // 🎓 LEARNING NOTES
 // ============================================================================
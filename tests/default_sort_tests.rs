@@ -0,0 +1,62 @@
+// Default sort order tests - DEFAULT_SORT_ORDER applies when no ?sort= is given
+//
+// The default comes from the environment, so this test runs in its own test
+// binary with DEFAULT_SORT_ORDER=asc set before the app starts.
+
+mod common;
+
+use rust_api_crud::create_app;
+use rust_api_crud::models::{User, UserListResponse};
+use serde_json::json;
+use uuid::Uuid;
+
+#[tokio::test]
+async fn test_default_sort_order_asc() {
+    std::env::set_var("DEFAULT_SORT_ORDER", "asc");
+    let Some(url) = common::try_spawn_app(create_app) else { return };
+    let client = reqwest::Client::new();
+    let suffix = Uuid::new_v4();
+
+    let mut created = Vec::new();
+    for i in 1..=2 {
+        let user: User = client
+            .post(format!("{}/users", url))
+            .json(&json!({
+                "name": format!("Ascending {}", i),
+                "email": format!("asc{}-{}@example.com", i, suffix)
+            }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        created.push(user);
+    }
+
+    let result: UserListResponse = client
+        .get(format!("{}/users?per_page=100", url))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    // Oldest first across the whole page
+    assert!(result.users.len() >= 2);
+    assert!(result
+        .users
+        .windows(2)
+        .all(|pair| pair[0].created_at <= pair[1].created_at));
+
+    // And our older user precedes our newer one
+    let position = |id| result.users.iter().position(|user| user.id == id);
+    if let (Some(first), Some(second)) = (position(created[0].id), position(created[1].id)) {
+        assert!(first < second);
+    }
+
+    for user in &created {
+        let _ = client.delete(format!("{}/users/{}", url, user.id)).send().await;
+    }
+}
@@ -0,0 +1,40 @@
+// Email autosuffix tests - ENABLE_EMAIL_AUTOSUFFIX=true retries conflicting emails
+//
+// The flag comes from the environment, so this test runs in its own test
+// binary with the flag set before the app starts.
+
+mod common;
+
+use rust_api_crud::create_app;
+use rust_api_crud::models::User;
+use serde_json::json;
+use uuid::Uuid;
+
+#[tokio::test]
+async fn test_duplicate_email_gets_suffix() {
+    std::env::set_var("ENABLE_EMAIL_AUTOSUFFIX", "true");
+    let Some(url) = common::try_spawn_app(create_app) else { return };
+    let client = reqwest::Client::new();
+
+    let local = format!("alice-{}", Uuid::new_v4().simple());
+    let email = format!("{}@example.com", local);
+
+    let mut created = Vec::new();
+    for _ in 0..2 {
+        let response = client
+            .post(format!("{}/users", url))
+            .json(&json!({ "name": "Alice", "email": email }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 201);
+        created.push(response.json::<User>().await.unwrap());
+    }
+
+    assert_eq!(created[0].email, email);
+    assert_eq!(created[1].email, format!("{}+1@example.com", local));
+
+    for user in &created {
+        let _ = client.delete(format!("{}/users/{}", url, user.id)).send().await;
+    }
+}
@@ -0,0 +1,68 @@
+// Email canonicalization tests - gmail-style variants as duplicates
+//
+// EMAIL_CANONICALIZE is part of the app's Config, so the test serves one app
+// with it off and one with it on, both on the same database.
+
+mod common;
+
+use rust_api_crud::create_app;
+use rust_api_crud::models::{canonical_email, User};
+use serde_json::json;
+use uuid::Uuid;
+
+#[test]
+fn test_canonical_email() {
+    assert_eq!(canonical_email("John.Doe@gmail.com"), "johndoe@gmail.com");
+    assert_eq!(canonical_email(" j.o.h.n.doe+news@GMAIL.com "), "johndoe@gmail.com");
+    assert_eq!(canonical_email("john.doe@googlemail.com"), "johndoe@gmail.com");
+    // Other providers keep dots and +tags
+    assert_eq!(canonical_email("John.Doe+x@Example.com"), "john.doe+x@example.com");
+    assert_eq!(canonical_email("not-an-email"), "not-an-email");
+}
+
+fn spawn_app(canonicalize: bool) -> Option<String> {
+    let mut config = common::try_test_config()?;
+    config.features.email_canonicalize = canonicalize;
+    Some(common::spawn_app_with_config(create_app, config))
+}
+
+#[tokio::test]
+async fn test_gmail_variants_conflict_only_when_enabled() {
+    let Some(plain_url) = spawn_app(false) else { return };
+    let Some(url) = spawn_app(true) else { return };
+    let client = reqwest::Client::new();
+    let create = |url: &str, email: String| {
+        client
+            .post(format!("{}/users", url))
+            .json(&json!({ "name": "Canonical", "email": email }))
+            .send()
+    };
+
+    let mut ids = Vec::new();
+
+    // Disabled: the two spellings are different users
+    let local = format!("john{}", Uuid::new_v4().simple());
+    let variant = format!("{}+promo@gmail.com", local.replacen("john", "jo.hn", 1));
+    for email in [format!("{}@gmail.com", local), variant] {
+        let response = create(&plain_url, email).await.unwrap();
+        assert_eq!(response.status(), 201);
+        ids.push(response.json::<User>().await.unwrap().id);
+    }
+
+    // Enabled: a dotted/+tagged variant of a stored address is a 409
+    let local = format!("jane{}", Uuid::new_v4().simple());
+    let response = create(&url, format!("{}@gmail.com", local)).await.unwrap();
+    assert_eq!(response.status(), 201);
+    let user: User = response.json().await.unwrap();
+    // The address is stored as typed
+    assert_eq!(user.email, format!("{}@gmail.com", local));
+    ids.push(user.id);
+
+    let variant = format!("{}+promo@gmail.com", local.replacen("jane", "ja.ne", 1));
+    let response = create(&url, variant).await.unwrap();
+    assert_eq!(response.status(), 409);
+
+    for id in ids {
+        let _ = client.delete(format!("{}/users/{}", url, id)).send().await;
+    }
+}
@@ -0,0 +1,162 @@
+// Email change tests - the new email only applies after confirming its token
+//
+// There's no mailer, so tests read the token straight from email_change_requests.
+
+mod common;
+
+use rust_api_crud::create_app;
+use rust_api_crud::error::ErrorCode;
+use rust_api_crud::models::{ErrorResponse, PendingEmailChange, User};
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+async fn create_user(client: &reqwest::Client, url: &str) -> User {
+    client
+        .post(format!("{}/users", url))
+        .json(&json!({
+            "name": "Email Change",
+            "email": format!("old-{}@example.com", Uuid::new_v4())
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap()
+}
+
+async fn pending_token(pool: &PgPool, user_id: Uuid) -> String {
+    sqlx::query_scalar("SELECT token FROM email_change_requests WHERE user_id = $1")
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_email_change_applies_only_after_confirmation() {
+    let Some(pool) = common::try_setup_test_db().await else { return };
+    let Some(url) = common::try_spawn_app(create_app) else { return };
+    let client = reqwest::Client::new();
+    let user = create_user(&client, &url).await;
+    let new_email = format!("new-{}@example.com", Uuid::new_v4());
+
+    let response = client
+        .post(format!("{}/users/{}/email-change", url, user.id))
+        .json(&json!({ "email": new_email }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 202);
+    let pending: PendingEmailChange = response.json().await.unwrap();
+    assert_eq!(pending.pending_email, new_email);
+
+    // Not applied yet: the old email is still the live one
+    let current: User = client
+        .get(format!("{}/users/{}", url, user.id))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(current.email, user.email);
+
+    let token = pending_token(&pool, user.id).await;
+    let response = client
+        .get(format!("{}/users/email-change/confirm?token={}", url, token))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    let confirmed: User = response.json().await.unwrap();
+    assert_eq!(confirmed.id, user.id);
+    assert_eq!(confirmed.email, new_email);
+    assert!(confirmed.email_updated_at.is_some());
+
+    // Tokens are single-use
+    let response = client
+        .get(format!("{}/users/email-change/confirm?token={}", url, token))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+    let body: ErrorResponse = response.json().await.unwrap();
+    assert_eq!(body.code, Some(ErrorCode::InvalidEmailChangeToken));
+
+    let _ = client.delete(format!("{}/users/{}", url, user.id)).send().await;
+}
+
+#[tokio::test]
+async fn test_expired_token_is_rejected() {
+    let Some(pool) = common::try_setup_test_db().await else { return };
+    let Some(url) = common::try_spawn_app(create_app) else { return };
+    let client = reqwest::Client::new();
+    let user = create_user(&client, &url).await;
+
+    let response = client
+        .post(format!("{}/users/{}/email-change", url, user.id))
+        .json(&json!({ "email": format!("late-{}@example.com", Uuid::new_v4()) }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 202);
+
+    sqlx::query("UPDATE email_change_requests SET expires_at = NOW() - INTERVAL '1 minute' WHERE user_id = $1")
+        .bind(user.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let token = pending_token(&pool, user.id).await;
+
+    let response = client
+        .get(format!("{}/users/email-change/confirm?token={}", url, token))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+    let body: ErrorResponse = response.json().await.unwrap();
+    assert_eq!(body.code, Some(ErrorCode::InvalidEmailChangeToken));
+
+    // The email didn't change
+    let current: User = client
+        .get(format!("{}/users/{}", url, user.id))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(current.email, user.email);
+
+    let _ = client.delete(format!("{}/users/{}", url, user.id)).send().await;
+}
+
+#[tokio::test]
+async fn test_email_change_for_unknown_user_or_taken_email() {
+    let Some(url) = common::try_spawn_app(create_app) else { return };
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(format!("{}/users/{}/email-change", url, Uuid::new_v4()))
+        .json(&json!({ "email": "nobody@example.com" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 404);
+
+    let first = create_user(&client, &url).await;
+    let second = create_user(&client, &url).await;
+    let response = client
+        .post(format!("{}/users/{}/email-change", url, second.id))
+        .json(&json!({ "email": first.email }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 409);
+
+    for id in [first.id, second.id] {
+        let _ = client.delete(format!("{}/users/{}", url, id)).send().await;
+    }
+}
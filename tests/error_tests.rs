@@ -0,0 +1,219 @@
+// Error mapping tests - how database failures surface as HTTP responses
+
+use std::borrow::Cow;
+use std::fmt;
+use std::time::Duration;
+
+use axum::http::StatusCode;
+use rust_api_crud::create_app;
+use rust_api_crud::error::{ApiError, ErrorCode};
+use rust_api_crud::models::ErrorResponse;
+use sqlx::error::{DatabaseError, ErrorKind};
+use sqlx::postgres::PgPoolOptions;
+use uuid::Uuid;
+
+mod common;
+
+// ============================================================================
+// sqlx::Error -> ApiError conversion
+// ============================================================================
+
+// A hand-built database error, so each ErrorKind can be fed to the conversion
+// without provoking real constraint violations
+#[derive(Debug)]
+struct FakeDatabaseError {
+    kind: ErrorKind,
+    constraint: Option<&'static str>,
+}
+
+impl fmt::Display for FakeDatabaseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "fake database error: {:?}", self.kind)
+    }
+}
+
+impl std::error::Error for FakeDatabaseError {}
+
+impl DatabaseError for FakeDatabaseError {
+    fn message(&self) -> &str {
+        "fake database error"
+    }
+
+    fn code(&self) -> Option<Cow<'_, str>> {
+        None
+    }
+
+    fn as_error(&self) -> &(dyn std::error::Error + Send + Sync + 'static) {
+        self
+    }
+
+    fn as_error_mut(&mut self) -> &mut (dyn std::error::Error + Send + Sync + 'static) {
+        self
+    }
+
+    fn into_error(self: Box<Self>) -> Box<dyn std::error::Error + Send + Sync + 'static> {
+        self
+    }
+
+    fn constraint(&self) -> Option<&str> {
+        self.constraint
+    }
+
+    fn kind(&self) -> ErrorKind {
+        match self.kind {
+            ErrorKind::UniqueViolation => ErrorKind::UniqueViolation,
+            ErrorKind::ForeignKeyViolation => ErrorKind::ForeignKeyViolation,
+            ErrorKind::NotNullViolation => ErrorKind::NotNullViolation,
+            ErrorKind::CheckViolation => ErrorKind::CheckViolation,
+            _ => ErrorKind::Other,
+        }
+    }
+}
+
+fn database_error(kind: ErrorKind, constraint: Option<&'static str>) -> sqlx::Error {
+    sqlx::Error::Database(Box::new(FakeDatabaseError { kind, constraint }))
+}
+
+#[test]
+fn test_row_not_found_maps_to_404() {
+    let error = ApiError::from(sqlx::Error::RowNotFound);
+    assert_eq!(error.status(), StatusCode::NOT_FOUND);
+}
+
+#[test]
+fn test_error_codes_serialize_as_screaming_snake_case() {
+    let body = ErrorResponse {
+        error: "user not found".to_string(),
+        code: Some(ApiError::NotFound.code()),
+        fields: None,
+    };
+    assert_eq!(
+        serde_json::to_value(&body).unwrap(),
+        serde_json::json!({ "error": "user not found", "code": "USER_NOT_FOUND" })
+    );
+
+    let code = serde_json::to_value(ApiError::Conflict.code()).unwrap();
+    assert_eq!(code, "CONFLICT");
+}
+
+#[test]
+fn test_unique_violation_maps_to_409() {
+    let error = ApiError::from(database_error(ErrorKind::UniqueViolation, Some("users_email_key")));
+    assert_eq!(error.status(), StatusCode::CONFLICT);
+    assert_eq!(error.message(), "email already exists");
+    assert_eq!(error.code(), ErrorCode::EmailTaken);
+
+    let error = ApiError::from(database_error(ErrorKind::UniqueViolation, None));
+    assert_eq!(error.status(), StatusCode::CONFLICT);
+}
+
+#[test]
+fn test_foreign_key_violation_maps_to_409() {
+    let error = ApiError::from(database_error(ErrorKind::ForeignKeyViolation, None));
+    assert_eq!(error.status(), StatusCode::CONFLICT);
+}
+
+#[test]
+fn test_check_and_not_null_violations_map_to_400() {
+    let error = ApiError::from(database_error(ErrorKind::CheckViolation, None));
+    assert_eq!(error.status(), StatusCode::BAD_REQUEST);
+
+    let error = ApiError::from(database_error(ErrorKind::NotNullViolation, None));
+    assert_eq!(error.status(), StatusCode::BAD_REQUEST);
+}
+
+#[test]
+fn test_pool_timed_out_maps_to_503() {
+    let error = ApiError::from(sqlx::Error::PoolTimedOut);
+    assert_eq!(error.status(), StatusCode::SERVICE_UNAVAILABLE);
+}
+
+#[test]
+fn test_pool_closed_maps_to_503() {
+    let error = ApiError::from(sqlx::Error::PoolClosed);
+    assert_eq!(error.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(error.message(), "server shutting down");
+}
+
+#[test]
+fn test_other_errors_map_to_500() {
+    let error = ApiError::from(database_error(ErrorKind::Other, None));
+    assert_eq!(error.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+    let error = ApiError::from(sqlx::Error::Protocol("unexpected message".to_string()));
+    assert_eq!(error.status(), StatusCode::INTERNAL_SERVER_ERROR);
+}
+
+// ============================================================================
+// Pool exhaustion -> 503 + Retry-After
+// ============================================================================
+
+#[tokio::test]
+async fn test_pool_timeout_returns_503_with_retry_after() {
+    let Some(config) = common::try_test_config() else { return };
+    // A single-connection pool with a short acquire timeout
+    let pool = PgPoolOptions::new()
+        .max_connections(1)
+        .acquire_timeout(Duration::from_millis(300))
+        .connect(&config.database.url)
+        .await
+        .expect("Failed to create test database pool");
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://127.0.0.1:{}", listener.local_addr().unwrap().port());
+    let app = create_app(pool.clone(), config);
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    // Hold the only connection so the handler can't acquire one
+    let held = pool.acquire().await.expect("Failed to acquire connection");
+
+    let response = reqwest::get(format!("{}/users/{}", url, Uuid::new_v4()))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 503);
+    assert_eq!(response.headers()["retry-after"], "1");
+    let body: ErrorResponse = response.json().await.unwrap();
+    assert_eq!(body.error, "service temporarily unavailable");
+
+    // Once the connection is released, the same request is a normal 404
+    drop(held);
+    let response = reqwest::get(format!("{}/users/{}", url, Uuid::new_v4()))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 404);
+}
+
+// ============================================================================
+// Closed pool (shutdown) -> 503
+// ============================================================================
+
+#[tokio::test]
+async fn test_closed_pool_returns_503() {
+    let Some(config) = common::try_test_config() else { return };
+    let pool = PgPoolOptions::new()
+        .max_connections(1)
+        .connect(&config.database.url)
+        .await
+        .expect("Failed to create test database pool");
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://127.0.0.1:{}", listener.local_addr().unwrap().port());
+    let app = create_app(pool.clone(), config);
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    // What graceful shutdown does before in-flight requests finish
+    pool.close().await;
+
+    let response = reqwest::get(format!("{}/users/{}", url, Uuid::new_v4()))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 503);
+    let body: ErrorResponse = response.json().await.unwrap();
+    assert_eq!(body.error, "server shutting down");
+}
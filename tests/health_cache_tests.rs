@@ -0,0 +1,61 @@
+// Health cache tests - rapid /health/db probes share one database check
+// The checks here are counting stand-ins, so no database is needed
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use futures::future::join_all;
+use rust_api_crud::db::{DbHealth, HealthCache};
+
+const HEALTHY: DbHealth = DbHealth { read: true, write: true };
+const WRITE_FAILING: DbHealth = DbHealth { read: true, write: false };
+
+// Wraps a fixed result, counting how often the "database" was actually checked
+async fn counted(calls: &AtomicUsize, result: DbHealth) -> DbHealth {
+    calls.fetch_add(1, Ordering::SeqCst);
+    tokio::time::sleep(Duration::from_millis(5)).await;
+    result
+}
+
+#[tokio::test]
+async fn test_rapid_probes_check_once_within_ttl() {
+    let cache = HealthCache::new(Duration::from_secs(60));
+    let calls = AtomicUsize::new(0);
+
+    // A burst of concurrent probes, then a few more one after another
+    let results = join_all((0..20).map(|_| cache.get_or_check(|| counted(&calls, HEALTHY)))).await;
+    for _ in 0..5 {
+        assert_eq!(cache.get_or_check(|| counted(&calls, HEALTHY)).await, HEALTHY);
+    }
+
+    assert!(results.iter().all(|health| *health == HEALTHY));
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_checks_again_after_ttl() {
+    let cache = HealthCache::new(Duration::from_millis(50));
+    let calls = AtomicUsize::new(0);
+
+    cache.get_or_check(|| counted(&calls, HEALTHY)).await;
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    cache.get_or_check(|| counted(&calls, HEALTHY)).await;
+
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_failures_are_not_cached() {
+    let cache = HealthCache::new(Duration::from_secs(60));
+    let calls = AtomicUsize::new(0);
+
+    assert_eq!(cache.get_or_check(|| counted(&calls, WRITE_FAILING)).await, WRITE_FAILING);
+
+    // Within the TTL, but the failure wasn't kept: this probe checks again and sees recovery
+    assert_eq!(cache.get_or_check(|| counted(&calls, HEALTHY)).await, HEALTHY);
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+    // The healthy result is cached as usual
+    assert_eq!(cache.get_or_check(|| counted(&calls, WRITE_FAILING)).await, HEALTHY);
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+}
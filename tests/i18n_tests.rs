@@ -0,0 +1,33 @@
+// Localization tests - Accept-Language parsing and the message catalog
+
+use rust_api_crud::error::ErrorCode;
+use rust_api_crud::i18n::{message, Locale};
+
+#[test]
+fn test_accept_language_picks_supported_locale() {
+    assert_eq!(Locale::from_accept_language("pt"), Locale::Pt);
+    assert_eq!(Locale::from_accept_language("pt-BR,pt;q=0.9,en;q=0.8"), Locale::Pt);
+    assert_eq!(Locale::from_accept_language("es-MX"), Locale::Es);
+}
+
+#[test]
+fn test_accept_language_respects_quality_values() {
+    assert_eq!(Locale::from_accept_language("en;q=0.5, es;q=0.9"), Locale::Es);
+    assert_eq!(Locale::from_accept_language("fr-FR, pt;q=0.8, en;q=0.5"), Locale::Pt);
+}
+
+#[test]
+fn test_accept_language_falls_back_to_english() {
+    assert_eq!(Locale::from_accept_language("fr-FR, de"), Locale::En);
+    assert_eq!(Locale::from_accept_language(""), Locale::En);
+    assert_eq!(Locale::from_accept_language("*"), Locale::En);
+}
+
+#[test]
+fn test_message_catalog() {
+    assert_eq!(message(ErrorCode::EmailTaken, Locale::En), Some("email already exists"));
+    assert_eq!(message(ErrorCode::EmailTaken, Locale::Pt), Some("o e-mail já está em uso"));
+    assert_eq!(message(ErrorCode::UserNotFound, Locale::Es), Some("usuario no encontrado"));
+    // Dynamic messages have no catalog entry
+    assert_eq!(message(ErrorCode::BadRequest, Locale::Pt), None);
+}
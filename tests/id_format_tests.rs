@@ -0,0 +1,88 @@
+// ID format tests - ID_FORMAT=uuidv4|uuidv7|ulid for new users
+//
+// The setting is part of the app's Config, so each format gets its own app,
+// all on the same database.
+
+mod common;
+
+use rust_api_crud::create_app;
+use rust_api_crud::models::id::{decode_ulid, encode_ulid, IdFormat};
+use rust_api_crud::models::User;
+use serde_json::json;
+use uuid::Uuid;
+
+#[test]
+fn test_ulid_round_trip() {
+    let id = Uuid::parse_str("01563e3a-b5d3-d676-4c61-efb99302bd5b").unwrap();
+    let ulid = encode_ulid(id);
+
+    assert_eq!(ulid, "01ARZ3NDEKTSV4RRFFQ69G5FAV");
+    assert_eq!(decode_ulid(&ulid), Some(id));
+    // Crockford decoding is case-insensitive
+    assert_eq!(decode_ulid(&ulid.to_lowercase()), Some(id));
+}
+
+#[test]
+fn test_invalid_ulids_are_rejected() {
+    // Too short, 'U' isn't in the alphabet, and 8 as the first char overflows 128 bits
+    assert_eq!(decode_ulid("01ARZ3NDEK"), None);
+    assert_eq!(decode_ulid("01ARZ3NDEKTSV4RRFFQ69G5FAU"), None);
+    assert_eq!(decode_ulid("81ARZ3NDEKTSV4RRFFQ69G5FAV"), None);
+}
+
+fn spawn_app(id_format: IdFormat) -> Option<String> {
+    let mut config = common::try_test_config()?;
+    config.features.id_format = id_format;
+    Some(common::spawn_app_with_config(create_app, config))
+}
+
+#[tokio::test]
+async fn test_id_formats() {
+    let Some(v7_url) = spawn_app(IdFormat::UuidV7) else { return };
+    let Some(url) = spawn_app(IdFormat::Ulid) else { return };
+    let client = reqwest::Client::new();
+    let token = Uuid::new_v4().simple().to_string();
+    let create = |url: &str, format: &str| {
+        client
+            .post(format!("{}/users", url))
+            .json(&json!({
+                "name": format!("Id {}", format),
+                "email": format!("id-{}-{}@example.com", format, token)
+            }))
+            .send()
+    };
+
+    // uuidv7: a time-ordered UUID
+    let v7: User = create(&v7_url, "uuidv7").await.unwrap().json().await.unwrap();
+    assert_eq!(v7.id.get_version_num(), 7);
+
+    // ulid: created, then fetched, updated and deleted by its ULID string
+    let user: User = create(&url, "ulid").await.unwrap().json().await.unwrap();
+    let ulid = encode_ulid(user.id);
+
+    // The leading 48 bits are the creation time in milliseconds
+    let millis = (user.id.as_u128() >> 80) as i64;
+    assert!((millis - user.created_at.timestamp_millis()).abs() < 60_000);
+
+    let response = client.get(format!("{}/users/{}", url, ulid)).send().await.unwrap();
+    assert_eq!(response.status(), 200);
+    let fetched: User = response.json().await.unwrap();
+    assert_eq!(fetched.id, user.id);
+
+    let response = client
+        .put(format!("{}/users/{}", url, ulid))
+        .json(&json!({ "name": "Id ulid renamed" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+
+    // The UUID form keeps working
+    let response = client.get(format!("{}/users/{}", url, user.id)).send().await.unwrap();
+    assert_eq!(response.status(), 200);
+
+    let response = client.delete(format!("{}/users/{}", url, ulid)).send().await.unwrap();
+    assert_eq!(response.status(), 204);
+
+    let _ = client.delete(format!("{}/users/{}", url, v7.id)).send().await;
+}
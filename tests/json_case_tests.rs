@@ -0,0 +1,107 @@
+// JSON case tests - snake_case (default) vs camelCase response bodies
+//
+// The case is part of the app's Config, so a snake_case app and a camelCase
+// app can serve side by side in the same test binary.
+
+use rust_api_crud::create_app;
+use rust_api_crud::models::response::{with_json_case, Cased, JsonCase};
+use rust_api_crud::models::{PaginationMeta, User};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+mod common;
+
+fn spawn_app(json_case: JsonCase) -> Option<String> {
+    let mut config = common::try_test_config()?;
+    config.features.json_case = json_case;
+    Some(common::spawn_app_with_config(create_app, config))
+}
+
+#[tokio::test]
+async fn test_camel_case_responses() {
+    let Some(snake_url) = spawn_app(JsonCase::Snake) else { return };
+    let Some(camel_url) = spawn_app(JsonCase::Camel) else { return };
+    let client = reqwest::Client::new();
+
+    // Default: snake_case
+    let created: Value = client
+        .post(format!("{}/users", snake_url))
+        .json(&json!({ "name": "Snake Case", "email": format!("snake-{}@example.com", Uuid::new_v4()) }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert!(created.get("created_at").is_some());
+    assert!(created.get("createdAt").is_none());
+
+    // Cased is a no-op in snake mode: same JSON as serializing User directly
+    let user: User = serde_json::from_value(created.clone()).unwrap();
+    assert_eq!(serde_json::to_value(Cased(user)).unwrap(), created);
+
+    // camelCase: every multi-word key is renamed, including the list envelope
+    let id = created["id"].as_str().unwrap();
+    let user: Value = client
+        .get(format!("{}/users/{}", camel_url, id))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert!(user.get("createdAt").is_some());
+    assert!(user.get("updatedAt").is_some());
+    assert!(user.get("created_at").is_none());
+
+    let list: Value = client
+        .get(format!("{}/users?per_page=1", camel_url))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(list["perPage"], 1);
+    assert!(list.get("totalPages").is_some());
+    assert!(list["users"][0].get("createdAt").is_some());
+
+    // The snake_case app is unaffected
+    let user: Value = client
+        .get(format!("{}/users/{}", snake_url, id))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert!(user.get("created_at").is_some());
+
+    let _ = client.delete(format!("{}/users/{}", snake_url, id)).send().await;
+}
+
+#[tokio::test]
+async fn test_case_is_scoped_not_global() {
+    let pagination = PaginationMeta {
+        total: 1,
+        per_page: 1,
+        total_pages: 1,
+    };
+
+    let camel = with_json_case(JsonCase::Camel, async {
+        serde_json::to_value(Cased(pagination.clone())).unwrap()
+    })
+    .await;
+    assert!(camel.get("perPage").is_some());
+
+    // Outside of a scope it's snake_case again
+    let snake = serde_json::to_value(Cased(pagination)).unwrap();
+    assert!(snake.get("per_page").is_some());
+}
+
+#[test]
+fn test_json_case_parse() {
+    assert_eq!(JsonCase::parse("snake"), Some(JsonCase::Snake));
+    assert_eq!(JsonCase::parse("camel"), Some(JsonCase::Camel));
+    assert_eq!(JsonCase::parse("kebab"), None);
+}
@@ -0,0 +1,72 @@
+// Content negotiation tests - Accept: application/msgpack vs the JSON default
+
+mod common;
+
+use rust_api_crud::create_app;
+use rust_api_crud::models::User;
+use rust_api_crud::negotiation::ResponseFormat;
+use serde_json::json;
+use uuid::Uuid;
+
+#[test]
+fn test_accept_header_picks_format() {
+    assert_eq!(ResponseFormat::from_accept("application/msgpack"), ResponseFormat::MsgPack);
+    assert_eq!(ResponseFormat::from_accept("application/x-msgpack"), ResponseFormat::MsgPack);
+    assert_eq!(ResponseFormat::from_accept("application/json"), ResponseFormat::Json);
+    assert_eq!(ResponseFormat::from_accept("*/*"), ResponseFormat::Json);
+    assert_eq!(ResponseFormat::from_accept("text/html"), ResponseFormat::Json);
+    assert_eq!(
+        ResponseFormat::from_accept("application/json;q=0.5, application/msgpack"),
+        ResponseFormat::MsgPack
+    );
+    assert_eq!(
+        ResponseFormat::from_accept("application/msgpack;q=0.2, application/json"),
+        ResponseFormat::Json
+    );
+    assert_eq!(ResponseFormat::from_accept("application/msgpack;q=0"), ResponseFormat::Json);
+}
+
+#[tokio::test]
+async fn test_get_user_as_msgpack() {
+    let Some(url) = common::try_spawn_app(create_app) else { return };
+    let client = reqwest::Client::new();
+
+    let email = format!("msgpack-{}@example.com", Uuid::new_v4());
+    let created: User = client
+        .post(format!("{}/users", url))
+        .json(&json!({ "name": "Packed", "email": email }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    let response = client
+        .get(format!("{}/users/{}", url, created.id))
+        .header("Accept", "application/msgpack")
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["content-type"], "application/msgpack");
+    assert_eq!(response.headers()["vary"], "accept");
+
+    let body = response.bytes().await.unwrap();
+    let user: User = rmp_serde::from_slice(&body).unwrap();
+    assert_eq!(user.id, created.id);
+    assert_eq!(user.name, "Packed");
+    assert_eq!(user.email, email);
+}
+
+#[tokio::test]
+async fn test_json_is_the_default() {
+    let Some(url) = common::try_spawn_app(create_app) else { return };
+    let client = reqwest::Client::new();
+
+    let response = client.get(format!("{}/users", url)).send().await.unwrap();
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["content-type"], "application/json");
+}
@@ -0,0 +1,74 @@
+// Out-of-range page tests - OUT_OF_RANGE_PAGE=clamp|error for GET /users
+//
+// The setting is part of the app's Config, so each mode gets its own app,
+// all on the same database.
+
+mod common;
+
+use rust_api_crud::create_app;
+use rust_api_crud::error::ErrorCode;
+use rust_api_crud::models::{ErrorResponse, OutOfRangePage, User, UserListResponse};
+use serde_json::json;
+use uuid::Uuid;
+
+fn spawn_app(out_of_range_page: OutOfRangePage) -> Option<String> {
+    let mut config = common::try_test_config()?;
+    config.features.out_of_range_page = out_of_range_page;
+    Some(common::spawn_app_with_config(create_app, config))
+}
+
+#[tokio::test]
+async fn test_page_past_total_pages() {
+    let Some(url) = spawn_app(OutOfRangePage::Empty) else { return };
+    let Some(clamp_url) = spawn_app(OutOfRangePage::Clamp) else { return };
+    let Some(error_url) = spawn_app(OutOfRangePage::Error) else { return };
+    let client = reqwest::Client::new();
+    let token = Uuid::new_v4().simple().to_string();
+
+    // 3 matching users at 2 per page = 2 pages
+    let mut ids = Vec::new();
+    for i in 0..3 {
+        let user: User = client
+            .post(format!("{}/users", url))
+            .json(&json!({
+                "name": format!("Range {} {}", token, i),
+                "email": format!("range-{}-{}@example.com", i, token)
+            }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        ids.push(user.id);
+    }
+    let page_99 = |url: &str| format!("{}/users?per_page=2&page=99&search={}", url, token);
+
+    // Default: an empty page, as before
+    let list: UserListResponse = client.get(page_99(&url)).send().await.unwrap().json().await.unwrap();
+    assert!(list.users.is_empty());
+    assert_eq!((list.page, list.total_pages), (99, 2));
+
+    // clamp: the last page
+    let list: UserListResponse = client.get(page_99(&clamp_url)).send().await.unwrap().json().await.unwrap();
+    assert_eq!(list.page, 2);
+    assert_eq!(list.users.len(), 1);
+
+    // error: 416
+    let response = client.get(page_99(&error_url)).send().await.unwrap();
+    assert_eq!(response.status(), 416);
+    let body: ErrorResponse = response.json().await.unwrap();
+    assert_eq!(body.code, Some(ErrorCode::PageOutOfRange));
+
+    // Pages in range are unaffected
+    let response = client
+        .get(format!("{}/users?per_page=2&page=2&search={}", error_url, token))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+
+    for id in ids {
+        let _ = client.delete(format!("{}/users/{}", url, id)).send().await;
+    }
+}
@@ -0,0 +1,40 @@
+// Panic recovery tests - a panicking handler gets a 500 JSON body, not a
+// dropped connection, and the server keeps serving
+
+use axum::{routing::get, Router};
+use rust_api_crud::error::ErrorCode;
+use rust_api_crud::models::ErrorResponse;
+use rust_api_crud::with_panic_recovery;
+
+async fn spawn(router: Router) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://127.0.0.1:{}", listener.local_addr().unwrap().port());
+    tokio::spawn(async move {
+        axum::serve(listener, router).await.unwrap();
+    });
+    url
+}
+
+// Stands in for an unwrap on an unexpected None
+async fn boom() -> &'static str {
+    panic!("unexpected None")
+}
+
+#[tokio::test]
+async fn test_panicking_handler_returns_500_json() {
+    let router = Router::new()
+        .route("/panic", get(boom))
+        .route("/ok", get(|| async { "ok" }));
+    let url = spawn(with_panic_recovery(router)).await;
+
+    let response = reqwest::get(format!("{}/panic", url)).await.unwrap();
+
+    assert_eq!(response.status(), 500);
+    let body: ErrorResponse = response.json().await.unwrap();
+    assert_eq!(body.error, "internal server error");
+    assert_eq!(body.code, Some(ErrorCode::Internal));
+
+    // Same server, still up
+    let response = reqwest::get(format!("{}/ok", url)).await.unwrap();
+    assert_eq!(response.status(), 200);
+}
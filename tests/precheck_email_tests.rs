@@ -0,0 +1,65 @@
+// Email precheck tests - PRECHECK_EMAIL only changes how a duplicate is
+// detected, never the response
+//
+// The flag is part of the app's Config, so the test serves one app with it
+// off and one with it on, both on the same database.
+
+use rust_api_crud::create_app;
+use rust_api_crud::models::{ErrorResponse, User};
+use serde_json::json;
+use uuid::Uuid;
+
+mod common;
+
+fn spawn_app(precheck_email: bool) -> Option<String> {
+    let mut config = common::try_test_config()?;
+    config.features.precheck_email = precheck_email;
+    Some(common::spawn_app_with_config(create_app, config))
+}
+
+#[tokio::test]
+async fn test_duplicate_email_is_409_in_both_modes() {
+    let Some(url) = spawn_app(true) else { return };
+    let client = reqwest::Client::new();
+    let email = format!("precheck-{}@example.com", Uuid::new_v4());
+    let payload = json!({ "name": "Precheck", "email": email });
+
+    let user: User = client
+        .post(format!("{}/users", url))
+        .json(&payload)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    for precheck in [false, true] {
+        let Some(precheck_url) = spawn_app(precheck) else { return };
+
+        let response = client
+            .post(format!("{}/users", precheck_url))
+            .json(&payload)
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), 409, "PRECHECK_EMAIL={}", precheck);
+        let body: ErrorResponse = response.json().await.unwrap();
+        assert_eq!(body.error, "email already exists");
+    }
+
+    // With the precheck on, a new email still goes through
+    let response = client
+        .post(format!("{}/users", url))
+        .json(&json!({ "name": "Precheck", "email": format!("precheck-{}@example.com", Uuid::new_v4()) }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 201);
+    let other: User = response.json().await.unwrap();
+
+    for id in [user.id, other.id] {
+        let _ = client.delete(format!("{}/users/{}", url, id)).send().await;
+    }
+}
@@ -0,0 +1,171 @@
+// Rate limit tests - per-API-key quotas, in memory and in Postgres
+//
+// Quotas are part of the app's Config, so each test serves its own app. The
+// calculator routes never touch the database, so those apps don't need one.
+
+mod common;
+
+use std::collections::HashMap;
+
+use rust_api_crud::config::Config;
+use rust_api_crud::create_app;
+use rust_api_crud::rate_limit::{InMemoryRateLimiter, PgRateLimiter, RateLimitConfig, RateLimiter};
+use uuid::Uuid;
+
+async fn spawn_app(rate_limit: RateLimitConfig) -> String {
+    let mut config = Config::default();
+    config.limits.rate_limit = Some(rate_limit);
+    common::serve(create_app(common::lazy_pool(), config)).await
+}
+
+#[tokio::test]
+async fn test_key_over_quota_does_not_affect_other_keys() {
+    let url = spawn_app(RateLimitConfig {
+        per_key: HashMap::from([("partner-small".to_string(), 3), ("partner-large".to_string(), 100)]),
+        ..Default::default()
+    })
+    .await;
+    let client = reqwest::Client::new();
+    let calculate = |api_key: &'static str| {
+        client
+            .get(format!("{}/calculate?a=1&b=2&op=add", url))
+            .header("X-API-Key", api_key)
+            .send()
+    };
+
+    for _ in 0..3 {
+        assert_eq!(calculate("partner-small").await.unwrap().status(), 200);
+    }
+
+    let limited = calculate("partner-small").await.unwrap();
+    assert_eq!(limited.status(), 429);
+    let retry_after: u64 = limited.headers()["retry-after"].to_str().unwrap().parse().unwrap();
+    assert!((1..=60).contains(&retry_after), "Retry-After was {}", retry_after);
+
+    // The other key has its own counter
+    for _ in 0..5 {
+        assert_eq!(calculate("partner-large").await.unwrap().status(), 200);
+    }
+
+    // Health checks are never limited
+    let health = client.get(format!("{}/health", url)).header("X-API-Key", "partner-small");
+    assert_eq!(health.send().await.unwrap().status(), 200);
+}
+
+#[tokio::test]
+async fn test_unknown_keys_are_limited_by_ip() {
+    let url = spawn_app(RateLimitConfig {
+        default_limit: Some(3),
+        per_key: HashMap::from([("partner-a".to_string(), 100)]),
+        ..Default::default()
+    })
+    .await;
+    let client = reqwest::Client::new();
+    let calculate = |api_key: String| {
+        client
+            .get(format!("{}/calculate?a=1&b=2&op=add", url))
+            .header("X-API-Key", api_key)
+            .send()
+    };
+
+    // A fresh made-up key per request still counts against the one IP quota
+    for _ in 0..3 {
+        assert_eq!(calculate(Uuid::new_v4().to_string()).await.unwrap().status(), 200);
+    }
+    assert_eq!(calculate(Uuid::new_v4().to_string()).await.unwrap().status(), 429);
+
+    // A configured key has its own quota
+    assert_eq!(calculate("partner-a".to_string()).await.unwrap().status(), 200);
+}
+
+#[tokio::test]
+async fn test_in_memory_limiter_sweeps_expired_windows_once_per_window() {
+    let limiter = InMemoryRateLimiter::with_window(std::time::Duration::from_millis(200));
+
+    // One-off keys pile up within a window: no sweep on every request
+    for _ in 0..50 {
+        assert_eq!(limiter.hit(&Uuid::new_v4().to_string(), 1).await, Ok(()));
+    }
+    assert_eq!(limiter.tracked_keys(), 50);
+
+    // The first request after a window has passed sweeps them all
+    tokio::time::sleep(std::time::Duration::from_millis(250)).await;
+    assert_eq!(limiter.hit("key:returning", 1).await, Ok(()));
+    assert_eq!(limiter.tracked_keys(), 1);
+
+    // The returning key starts over in the next window
+    assert!(limiter.hit("key:returning", 1).await.is_err());
+    tokio::time::sleep(std::time::Duration::from_millis(250)).await;
+    assert_eq!(limiter.hit("key:returning", 1).await, Ok(()));
+}
+
+#[tokio::test]
+async fn test_postgres_limiter_is_shared_across_instances() {
+    let Some(pool) = common::try_setup_test_db().await else { return };
+
+    // Two "replicas" with their own limiter, one database
+    let first = PgRateLimiter::new(pool.clone(), Default::default());
+    let second = PgRateLimiter::new(pool.clone(), Default::default());
+    let key = format!("key:shared-{}", Uuid::new_v4());
+
+    // Windows are aligned to the minute; don't straddle a boundary mid-test
+    let seconds_left: f64 =
+        sqlx::query_scalar("SELECT (60 - extract(second FROM NOW()))::FLOAT8")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    if seconds_left < 2.0 {
+        tokio::time::sleep(std::time::Duration::from_secs_f64(seconds_left + 0.1)).await;
+    }
+
+    assert_eq!(first.hit(&key, 3).await, Ok(()));
+    assert_eq!(second.hit(&key, 3).await, Ok(()));
+    assert_eq!(first.hit(&key, 3).await, Ok(()));
+
+    // The quota is used up for both
+    let retry_after = second.hit(&key, 3).await.expect_err("fourth request should be limited");
+    assert!((1..=60).contains(&retry_after), "Retry-After was {}", retry_after);
+    assert!(first.hit(&key, 3).await.is_err());
+
+    // Other keys are counted separately
+    let other = format!("key:other-{}", Uuid::new_v4());
+    assert_eq!(second.hit(&other, 3).await, Ok(()));
+
+    sqlx::query("DELETE FROM rate_limit_windows WHERE key = $1 OR key = $2")
+        .bind(&key)
+        .bind(&other)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_postgres_limiter_prunes_windows_of_keys_that_never_return() {
+    let Some(pool) = common::try_setup_test_db().await else { return };
+    let limiter = PgRateLimiter::new(pool.clone(), Default::default());
+    let gone = format!("key:gone-{}", Uuid::new_v4());
+    let active = format!("key:active-{}", Uuid::new_v4());
+
+    // A window from an hour ago for a key that's never seen again
+    sqlx::query("INSERT INTO rate_limit_windows (key, window_start, count) VALUES ($1, NOW() - INTERVAL '1 hour', 5)")
+        .bind(&gone)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    // Any other key opening a window clears it
+    assert_eq!(limiter.hit(&active, 3).await, Ok(()));
+
+    let left: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM rate_limit_windows WHERE key = $1")
+        .bind(&gone)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(left, 0);
+
+    sqlx::query("DELETE FROM rate_limit_windows WHERE key = $1")
+        .bind(&active)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
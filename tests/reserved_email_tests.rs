@@ -0,0 +1,113 @@
+// Reserved email tests - RESERVED_EMAIL_LOCAL_PARTS blocks sign-ups (and email
+// changes) to addresses like postmaster@ and abuse@ on any domain
+//
+// The list is part of the app's Config, so the custom-list test serves its
+// own app on the same database.
+
+use rust_api_crud::create_app;
+use rust_api_crud::error::ErrorCode;
+use rust_api_crud::models::{is_reserved_email, ErrorResponse, User};
+use serde_json::json;
+use uuid::Uuid;
+
+mod common;
+
+fn spawn_app(reserved: &[&str]) -> Option<String> {
+    let mut config = common::try_test_config()?;
+    config.features.reserved_email_local_parts = reserved.iter().map(|part| part.to_string()).collect();
+    Some(common::spawn_app_with_config(create_app, config))
+}
+
+#[test]
+fn test_local_part_matches_ignoring_case_and_tags() {
+    let reserved = vec!["postmaster".to_string(), "abuse".to_string()];
+
+    assert!(is_reserved_email("postmaster@example.com", &reserved));
+    assert!(is_reserved_email(" PostMaster@Example.com ", &reserved));
+    assert!(is_reserved_email("abuse+reports@example.com", &reserved));
+
+    // Only the whole local part counts, and the domain never does
+    assert!(!is_reserved_email("postmaster2@example.com", &reserved));
+    assert!(!is_reserved_email("alice@postmaster.example.com", &reserved));
+}
+
+#[tokio::test]
+async fn test_default_list_blocks_postmaster() {
+    let Some(url) = common::try_spawn_app(create_app) else { return };
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(format!("{}/users", url))
+        .json(&json!({ "name": "Postmaster", "email": format!("Postmaster@{}.example.com", Uuid::new_v4()) }))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 403);
+    let body: ErrorResponse = response.json().await.unwrap();
+    assert_eq!(body.error, "reserved email");
+    assert_eq!(body.code, Some(ErrorCode::ReservedEmail));
+}
+
+#[tokio::test]
+async fn test_custom_list_replaces_the_defaults() {
+    let Some(url) = spawn_app(&["support"]) else { return };
+    let client = reqwest::Client::new();
+    let domain = format!("{}.example.com", Uuid::new_v4());
+
+    let response = client
+        .post(format!("{}/users", url))
+        .json(&json!({ "name": "Support", "email": format!("support@{}", domain) }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 403);
+
+    // Not on this list, so an ordinary sign-up
+    let response = client
+        .post(format!("{}/users", url))
+        .json(&json!({ "name": "Postmaster", "email": format!("postmaster@{}", domain) }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 201);
+    let user: User = response.json().await.unwrap();
+
+    let _ = client.delete(format!("{}/users/{}", url, user.id)).send().await;
+}
+
+#[tokio::test]
+async fn test_reserved_email_is_blocked_on_update_and_email_change() {
+    let Some(url) = common::try_spawn_app(create_app) else { return };
+    let client = reqwest::Client::new();
+    let domain = format!("{}.example.com", Uuid::new_v4());
+
+    let response = client
+        .post(format!("{}/users", url))
+        .json(&json!({ "name": "Alice", "email": format!("alice@{}", domain) }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 201);
+    let user: User = response.json().await.unwrap();
+
+    let response = client
+        .put(format!("{}/users/{}", url, user.id))
+        .json(&json!({ "email": format!("abuse@{}", domain) }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 403);
+    let body: ErrorResponse = response.json().await.unwrap();
+    assert_eq!(body.code, Some(ErrorCode::ReservedEmail));
+
+    let response = client
+        .post(format!("{}/users/{}/email-change", url, user.id))
+        .json(&json!({ "email": format!("Postmaster@{}", domain) }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 403);
+
+    let _ = client.delete(format!("{}/users/{}", url, user.id)).send().await;
+}
@@ -0,0 +1,103 @@
+// Reset endpoint tests - DELETE /users?confirm=<token>
+//
+// These tests wipe the users table, so they live in their own test binary
+// (cargo runs test binaries one after another) and serve the app in-process
+// with a known RESET_TOKEN.
+
+mod common;
+
+use std::sync::OnceLock;
+
+use rust_api_crud::create_app;
+use rust_api_crud::models::{User, UserListResponse};
+use serde_json::{json, Value};
+
+const RESET_TOKEN: &str = "test-reset-token";
+
+// None without a database (the tests skip)
+static SERVER_URL: OnceLock<Option<String>> = OnceLock::new();
+
+fn server_url() -> Option<String> {
+    SERVER_URL
+        .get_or_init(|| {
+            std::env::set_var("RESET_TOKEN", RESET_TOKEN);
+            common::try_spawn_app(create_app)
+        })
+        .clone()
+}
+
+#[tokio::test]
+async fn test_delete_all_users_with_valid_token() {
+    let Some(url) = server_url() else { return };
+    let client = reqwest::Client::new();
+
+    let mut ids = Vec::new();
+    for i in 1..=3 {
+        let response = client
+            .post(format!("{}/users", url))
+            .json(&json!({
+                "name": format!("Reset {}", i),
+                "email": format!("reset{}@example.com", i)
+            }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 201);
+        let user: User = response.json().await.unwrap();
+        ids.push(user.id);
+    }
+
+    let response = client
+        .delete(format!("{}/users?confirm={}", url, RESET_TOKEN))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+    let body: Value = response.json().await.unwrap();
+    assert!(body["deleted"].as_u64().unwrap() >= 3);
+
+    let list: UserListResponse = client
+        .get(format!("{}/users", url))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(list.total, 0);
+    assert!(list.users.is_empty());
+
+    // The streaming export is still a valid (empty) JSON array
+    let streamed = client
+        .get(format!("{}/users/stream.json", url))
+        .send()
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    assert_eq!(streamed, "[]");
+
+    // Purged users are gone, not "never existed"
+    for id in ids {
+        let response = client.get(format!("{}/users/{}", url, id)).send().await.unwrap();
+        assert_eq!(response.status(), 410);
+    }
+}
+
+#[tokio::test]
+async fn test_delete_all_users_rejects_wrong_or_missing_token() {
+    let Some(url) = server_url() else { return };
+    let client = reqwest::Client::new();
+
+    let wrong = client
+        .delete(format!("{}/users?confirm=not-the-token", url))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(wrong.status(), 403);
+
+    let missing = client.delete(format!("{}/users", url)).send().await.unwrap();
+    assert_eq!(missing.status(), 403);
+}
@@ -0,0 +1,22 @@
+// Schema status tests - GET /admin/schema compares applied migrations with
+// the ones embedded in the binary
+
+mod common;
+
+use rust_api_crud::create_app;
+use rust_api_crud::db::{run_migrations, SchemaStatus, MIGRATOR};
+
+#[tokio::test]
+async fn test_up_to_date_after_running_migrations() {
+    let Some(pool) = common::try_setup_test_db().await else { return };
+    run_migrations(&pool).await.expect("Failed to run migrations");
+    let Some(url) = common::try_spawn_app(create_app) else { return };
+
+    let response = reqwest::get(format!("{}/admin/schema", url)).await.unwrap();
+
+    assert_eq!(response.status(), 200);
+    let status: SchemaStatus = response.json().await.unwrap();
+    assert!(status.up_to_date);
+    assert_eq!(status.applied_count, MIGRATOR.iter().count() as i64);
+    assert_eq!(status.latest_version, MIGRATOR.iter().map(|migration| migration.version).max());
+}
@@ -0,0 +1,176 @@
+// Server tests - connection-level behavior of the custom listener
+
+use std::time::Duration;
+
+use rust_api_crud::config::Config;
+use rust_api_crud::create_app;
+use rust_api_crud::server::{serve, ServerSettings};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+mod common;
+
+async fn start_server(settings: ServerSettings) -> std::net::SocketAddr {
+    // These tests never touch the database, so a lazy pool is enough
+    let pool = common::lazy_pool();
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(serve(listener, create_app(pool, Config::default()), settings));
+
+    addr
+}
+
+// Read until the server closes the connection, or give up after `limit`
+async fn read_until_closed(stream: &mut TcpStream, limit: Duration) -> Option<Vec<u8>> {
+    let mut received = Vec::new();
+    tokio::time::timeout(limit, stream.read_to_end(&mut received))
+        .await
+        .ok()
+        .map(|_| received)
+}
+
+#[tokio::test]
+async fn test_partial_request_is_closed_after_header_timeout() {
+    let addr = start_server(ServerSettings {
+        header_read_timeout: Duration::from_millis(500),
+        keep_alive: true,
+        ..Default::default()
+    })
+    .await;
+
+    // Send a request head without the final blank line, then stall
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    stream
+        .write_all(b"GET /health HTTP/1.1\r\nHost: localhost\r\n")
+        .await
+        .unwrap();
+
+    let closed = read_until_closed(&mut stream, Duration::from_secs(5)).await;
+    assert!(closed.is_some(), "Server kept a stalled connection open");
+}
+
+#[tokio::test]
+async fn test_idle_keep_alive_connection_is_closed() {
+    let addr = start_server(ServerSettings {
+        header_read_timeout: Duration::from_millis(500),
+        keep_alive: true,
+        ..Default::default()
+    })
+    .await;
+
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    stream
+        .write_all(b"GET /health HTTP/1.1\r\nHost: localhost\r\n\r\n")
+        .await
+        .unwrap();
+
+    // The first request is answered, then the idle connection is closed
+    let received = read_until_closed(&mut stream, Duration::from_secs(5))
+        .await
+        .expect("Server kept an idle connection open");
+    let text = String::from_utf8_lossy(&received);
+    assert!(text.starts_with("HTTP/1.1 200"), "Unexpected response: {}", text);
+}
+
+#[tokio::test]
+async fn test_idle_connection_is_closed_after_keep_alive_timeout() {
+    // Plenty of time to send a request, but not to idle between requests
+    let addr = start_server(ServerSettings {
+        header_read_timeout: Duration::from_secs(30),
+        keep_alive: true,
+        keep_alive_timeout: Duration::from_millis(500),
+        ..Default::default()
+    })
+    .await;
+
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    stream
+        .write_all(b"GET /health HTTP/1.1\r\nHost: localhost\r\n\r\n")
+        .await
+        .unwrap();
+
+    let received = read_until_closed(&mut stream, Duration::from_secs(5))
+        .await
+        .expect("Server kept an idle connection open past HTTP_KEEP_ALIVE_TIMEOUT_SECS");
+    assert!(String::from_utf8_lossy(&received).starts_with("HTTP/1.1 200"));
+}
+
+#[tokio::test]
+async fn test_unresponsive_http2_connection_is_closed() {
+    let addr = start_server(ServerSettings {
+        http2: true,
+        keep_alive_timeout: Duration::from_millis(500),
+        ..Default::default()
+    })
+    .await;
+
+    // An h2c client that opens the connection, then never answers anything
+    // (including the server's pings)
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    stream.write_all(b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n").await.unwrap();
+    // Empty SETTINGS frame: length 0, type 0x4, no flags, stream 0
+    stream.write_all(&[0, 0, 0, 0x4, 0, 0, 0, 0, 0]).await.unwrap();
+
+    let received = read_until_closed(&mut stream, Duration::from_secs(5))
+        .await
+        .expect("Server kept an unresponsive HTTP/2 connection open");
+
+    // Walk the frames the server sent: one of them was a PING (type 0x6)
+    let mut frame_types = Vec::new();
+    let mut rest = &received[..];
+    while rest.len() >= 9 {
+        let length = u32::from_be_bytes([0, rest[0], rest[1], rest[2]]) as usize;
+        frame_types.push(rest[3]);
+        rest = &rest[(9 + length).min(rest.len())..];
+    }
+    assert!(frame_types.contains(&0x6), "No PING among frames {:?}", frame_types);
+}
+
+#[tokio::test]
+async fn test_complete_request_is_served() {
+    let addr = start_server(ServerSettings::default()).await;
+
+    let response = reqwest::get(format!("http://{}/health", addr)).await.unwrap();
+    assert_eq!(response.status(), 200);
+}
+
+#[tokio::test]
+async fn test_http2_request_is_served_when_enabled() {
+    let addr = start_server(ServerSettings {
+        http2: true,
+        ..Default::default()
+    })
+    .await;
+
+    // h2c with prior knowledge: the client speaks HTTP/2 from the first byte
+    let client = reqwest::Client::builder()
+        .http2_prior_knowledge()
+        .build()
+        .unwrap();
+    let response = client
+        .get(format!("http://{}/health", addr))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.version(), reqwest::Version::HTTP_2);
+
+    // HTTP/1.1 clients keep working on the same listener
+    let response = reqwest::get(format!("http://{}/health", addr)).await.unwrap();
+    assert_eq!(response.version(), reqwest::Version::HTTP_11);
+}
+
+#[tokio::test]
+async fn test_http2_is_refused_by_default() {
+    let addr = start_server(ServerSettings::default()).await;
+
+    let client = reqwest::Client::builder()
+        .http2_prior_knowledge()
+        .build()
+        .unwrap();
+    let result = client.get(format!("http://{}/health", addr)).send().await;
+
+    assert!(result.is_err(), "HTTP/1.1-only server answered an HTTP/2 request");
+}
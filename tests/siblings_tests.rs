@@ -0,0 +1,91 @@
+// Sibling tests - GET /users/:id/siblings returns the neighbors in list order
+//
+// Neighbors depend on every row in the table, so this runs in its own test
+// binary where no other test is inserting users at the same time. The sort
+// order is part of the app's Config, so each order gets its own app.
+
+mod common;
+
+use rust_api_crud::create_app;
+use rust_api_crud::models::{SortDirection, User, UserSiblings};
+use serde_json::json;
+use uuid::Uuid;
+
+fn spawn_app(default_sort_order: SortDirection) -> Option<String> {
+    let mut config = common::try_test_config()?;
+    config.features.default_sort_order = default_sort_order;
+    Some(common::spawn_app_with_config(create_app, config))
+}
+
+#[tokio::test]
+async fn test_middle_user_siblings() {
+    let Some(url) = spawn_app(SortDirection::Desc) else { return };
+    let Some(asc_url) = spawn_app(SortDirection::Asc) else { return };
+    let client = reqwest::Client::new();
+    let suffix = Uuid::new_v4();
+
+    let mut created = Vec::new();
+    for i in 1..=3 {
+        let user: User = client
+            .post(format!("{}/users", url))
+            .json(&json!({
+                "name": format!("Sibling {}", i),
+                "email": format!("sibling{}-{}@example.com", i, suffix)
+            }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        created.push(user);
+    }
+    let (first, middle, last) = (&created[0], &created[1], &created[2]);
+
+    // Oldest first: prev is the older user, next the newer one
+    let siblings: UserSiblings = client
+        .get(format!("{}/users/{}/siblings", asc_url, middle.id))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(siblings.prev.map(|user| user.id), Some(first.id));
+    assert_eq!(siblings.next.map(|user| user.id), Some(last.id));
+
+    // Newest first (the default): the neighbors swap sides
+    let siblings: UserSiblings = client
+        .get(format!("{}/users/{}/siblings", url, middle.id))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(siblings.prev.map(|user| user.id), Some(last.id));
+    assert_eq!(siblings.next.map(|user| user.id), Some(first.id));
+
+    // The newest user has nothing newer
+    let siblings: UserSiblings = client
+        .get(format!("{}/users/{}/siblings", url, last.id))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert!(siblings.prev.is_none());
+
+    // Unknown users are a 404
+    let response = client
+        .get(format!("{}/users/{}/siblings", url, Uuid::new_v4()))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 404);
+
+    for user in &created {
+        let _ = client.delete(format!("{}/users/{}", url, user.id)).send().await;
+    }
+}
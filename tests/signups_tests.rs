@@ -0,0 +1,85 @@
+// Signups tests - GET /users/signups buckets users by creation date
+//
+// Users are backdated to March 1999, a window no other test writes to.
+
+mod common;
+
+use chrono::{NaiveDate, TimeZone, Utc};
+use rust_api_crud::create_app;
+use rust_api_crud::models::{ErrorResponse, SignupCount, User};
+use serde_json::json;
+use uuid::Uuid;
+
+#[tokio::test]
+async fn test_daily_and_weekly_buckets() {
+    let Some(pool) = common::try_setup_test_db().await else { return };
+    let Some(url) = common::try_spawn_app(create_app) else { return };
+    let client = reqwest::Client::new();
+    let suffix = Uuid::new_v4();
+
+    // Two users on Mon 1 March, one on the 2nd and one on the 4th
+    let mut ids = Vec::new();
+    for (i, day) in [1, 1, 2, 4].into_iter().enumerate() {
+        let user: User = client
+            .post(format!("{}/users", url))
+            .json(&json!({ "name": "Signup", "email": format!("signup{}-{}@example.com", i, suffix) }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+
+        let created_at = Utc.with_ymd_and_hms(1999, 3, day, 10, 0, 0).unwrap();
+        sqlx::query("UPDATE users SET created_at = $1 WHERE id = $2")
+            .bind(created_at)
+            .bind(user.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        ids.push(user.id);
+    }
+
+    let signups = |query: &str| {
+        let request = client.get(format!("{}/users/signups?{}", url, query));
+        async move {
+            let response = request.send().await.unwrap();
+            assert_eq!(response.status(), 200);
+            response.json::<Vec<SignupCount>>().await.unwrap()
+        }
+    };
+    let date = |day: u32| NaiveDate::from_ymd_opt(1999, 3, day).unwrap();
+
+    let daily = signups("bucket=day&since=1999-03-01").await;
+    assert_eq!(
+        daily[..3],
+        [
+            SignupCount { date: date(1), count: 2 },
+            SignupCount { date: date(2), count: 1 },
+            SignupCount { date: date(4), count: 1 },
+        ]
+    );
+
+    // Weeks start on Monday, which 1 March 1999 was
+    let weekly = signups("bucket=week&since=1999-03-01T00:00:00Z").await;
+    assert_eq!(weekly[0], SignupCount { date: date(1), count: 4 });
+
+    // since is inclusive and cuts off earlier days
+    let daily = signups("since=1999-03-02").await;
+    assert_eq!(daily[0], SignupCount { date: date(2), count: 1 });
+
+    for id in ids {
+        let _ = client.delete(format!("{}/users/{}", url, id)).send().await;
+    }
+}
+
+#[tokio::test]
+async fn test_unknown_bucket_is_rejected() {
+    let Some(url) = common::try_spawn_app(create_app) else { return };
+
+    let response = reqwest::get(format!("{}/users/signups?bucket=hour", url)).await.unwrap();
+
+    assert_eq!(response.status(), 400);
+    let body: ErrorResponse = response.json().await.unwrap();
+    assert_eq!(body.fields.unwrap()[0].field, "bucket");
+}
@@ -0,0 +1,97 @@
+// Single-flight tests - overlapping identical creates share one INSERT, and
+// flights are never shared between app instances
+
+use std::time::Duration;
+
+use rust_api_crud::create_app;
+use rust_api_crud::models::User;
+use rust_api_crud::single_flight::SingleFlight;
+use serde_json::json;
+use sqlx::postgres::PgPoolOptions;
+use uuid::Uuid;
+
+mod common;
+
+#[tokio::test]
+async fn test_simultaneous_identical_creates_share_one_user() {
+    let Some(config) = common::try_test_config() else { return };
+    // One connection, held by the test, so the first create can't finish
+    // before the second one arrives
+    let pool = PgPoolOptions::new()
+        .max_connections(1)
+        .acquire_timeout(Duration::from_secs(10))
+        .connect(&config.database.url)
+        .await
+        .expect("Failed to create test database pool");
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://127.0.0.1:{}", listener.local_addr().unwrap().port());
+    let app = create_app(pool.clone(), config);
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let held = pool.acquire().await.unwrap();
+
+    // Same form submitted twice; the second differs only in email case/whitespace
+    let email = format!("double-submit-{}@example.com", Uuid::new_v4());
+    let client = reqwest::Client::new();
+    let submit = |email: String| {
+        let client = client.clone();
+        let url = format!("{}/users", url);
+        tokio::spawn(async move {
+            client
+                .post(url)
+                .json(&json!({ "name": "Double Submit", "email": email }))
+                .send()
+                .await
+                .unwrap()
+        })
+    };
+    let first = submit(email.clone());
+    let second = submit(format!(" {} ", email.to_uppercase()));
+
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    drop(held);
+
+    let first = first.await.unwrap();
+    let second = second.await.unwrap();
+    assert_eq!(first.status(), 201);
+    assert_eq!(second.status(), 201);
+
+    let first: User = first.json().await.unwrap();
+    let second: User = second.json().await.unwrap();
+    assert_eq!(first.id, second.id);
+
+    // Once the flight has landed, the same create is an ordinary duplicate
+    let response = client
+        .post(format!("{}/users", url))
+        .json(&json!({ "name": "Double Submit", "email": email }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 409);
+
+    let _ = client.delete(format!("{}/users/{}", url, first.id)).send().await;
+}
+
+#[tokio::test]
+async fn test_flights_are_shared_per_instance_only() {
+    let slow = |value: u32| async move {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        value
+    };
+
+    // Same instance, same key: the second caller gets the first one's result
+    let flights = SingleFlight::new();
+    let results = tokio::join!(flights.run("key", slow(1)), flights.run("key", slow(2)));
+    assert_eq!(results, (1, 1));
+
+    // Another instance (another app) has its own flights
+    let other = SingleFlight::new();
+    let results = tokio::join!(flights.run("key", slow(1)), other.run("key", slow(2)));
+    assert_eq!(results, (1, 2));
+
+    // Nothing is remembered once a flight lands
+    assert_eq!(flights.run("key", slow(3)).await, 3);
+}
@@ -0,0 +1,115 @@
+// Stats tests - /users/stats serves cached counts until /admin/stats/refresh
+//
+// Counts cover the whole table, so this runs in its own test binary where no
+// other test is inserting users at the same time.
+
+mod common;
+
+use rust_api_crud::config::Config;
+use rust_api_crud::state::AppState;
+use rust_api_crud::{create_app, create_app_with_state};
+use rust_api_crud::models::{User, UserStats};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+const ADMIN_TOKEN: &str = "test-admin-token";
+const REFRESH_PATH: &str = "/admin/stats/refresh?confirm=test-admin-token";
+
+// Served in-process with a known ADMIN_TOKEN; None without a database
+fn spawn_admin_app() -> Option<String> {
+    let mut config = common::try_test_config()?;
+    config.features.admin_token = Some(ADMIN_TOKEN.to_string());
+    Some(common::spawn_app_with_config(create_app, config))
+}
+
+#[tokio::test]
+async fn test_refresh_updates_cached_total() {
+    let Some(url) = spawn_admin_app() else { return };
+    let client = reqwest::Client::new();
+    let get_stats = |path: &'static str| {
+        let client = client.clone();
+        let url = format!("{}{}", url, path);
+        async move {
+            let response = client.get(url).send().await.unwrap();
+            assert_eq!(response.status(), 200);
+            response.json::<UserStats>().await.unwrap()
+        }
+    };
+
+    let before = get_stats(REFRESH_PATH).await;
+
+    // Two users on one domain, one on another (domains are counted lowercased)
+    let run = Uuid::new_v4();
+    let first_domain = format!("first-{}.example.com", run);
+    let second_domain = format!("second-{}.example.com", run);
+    let mut ids = Vec::new();
+    for email in [
+        format!("a@{}", first_domain),
+        format!("b@{}", first_domain.to_uppercase()),
+        format!("c@{}", second_domain),
+    ] {
+        let user: User = client
+            .post(format!("{}/users", url))
+            .json(&json!({ "name": "Stats", "email": email }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        ids.push(user.id);
+    }
+
+    // Still the cached value
+    let cached = get_stats("/users/stats").await;
+    assert_eq!(cached.total, before.total);
+    assert_eq!(cached.refreshed_at, before.refreshed_at);
+
+    // Refresh picks up the new users, and the plain endpoint now serves them
+    let refreshed = get_stats(REFRESH_PATH).await;
+    assert_eq!(refreshed.total, before.total + 3);
+    assert_eq!(refreshed.by_domain.get(&first_domain), Some(&2));
+    assert_eq!(refreshed.by_domain.get(&second_domain), Some(&1));
+
+    let cached = get_stats("/users/stats").await;
+    assert_eq!(cached.total, before.total + 3);
+
+    for id in ids {
+        let _ = client.delete(format!("{}/users/{}", url, id)).send().await;
+    }
+}
+
+#[tokio::test]
+async fn test_refresh_requires_the_admin_token() {
+    let Some(url) = spawn_admin_app() else { return };
+
+    for query in ["", "?confirm=", "?confirm=wrong-token"] {
+        let response = reqwest::get(format!("{}/admin/stats/refresh{}", url, query)).await.unwrap();
+        assert_eq!(response.status(), 403, "{:?}", query);
+        let body: Value = response.json().await.unwrap();
+        assert_eq!(body["code"], "INVALID_RESET_TOKEN");
+    }
+}
+
+#[tokio::test]
+async fn test_refresh_is_disabled_without_an_admin_token() {
+    let Some(pool) = common::try_setup_test_db().await else { return };
+    let url = common::serve(create_app_with_state(AppState::new(pool, Config::default()))).await;
+
+    let response = reqwest::get(format!("{}/admin/stats/refresh?confirm=", url)).await.unwrap();
+    assert_eq!(response.status(), 403);
+}
+
+#[tokio::test]
+async fn test_each_app_has_its_own_stats_cache() {
+    let Some(pool) = common::try_setup_test_db().await else { return };
+    let first = AppState::new(pool.clone(), Config::default());
+    let second = AppState::new(pool, Config::default());
+    let url = common::serve(create_app_with_state(first.clone())).await;
+
+    let response = reqwest::get(format!("{}/users/stats", url)).await.unwrap();
+    assert_eq!(response.status(), 200);
+
+    assert!(first.cache.user_stats.read().unwrap().is_some());
+    assert!(second.cache.user_stats.read().unwrap().is_none());
+}
@@ -0,0 +1,17 @@
+// Test database fallback - DB tests skip instead of panicking without DATABASE_URL
+//
+// Removes DATABASE_URL for the whole process, so this runs in its own test binary.
+
+mod common;
+
+#[tokio::test]
+async fn test_missing_database_url_skips_instead_of_panicking() {
+    std::env::remove_var("DATABASE_URL");
+
+    assert!(common::try_database_url().is_none());
+    assert!(common::try_setup_test_db().await.is_none());
+
+    // Blank counts as unset
+    std::env::set_var("DATABASE_URL", "");
+    assert!(common::try_setup_test_db().await.is_none());
+}
@@ -0,0 +1,86 @@
+// Timestamp serialization tests
+//
+// The format is scoped per request (with_timestamp_format, set from the
+// app's TIMESTAMP_FORMAT), so tests can switch it without touching other tests.
+
+mod common;
+
+use chrono::{TimeZone, Utc};
+use rust_api_crud::create_app;
+use rust_api_crud::models::timestamp::{with_timestamp_format, TimestampFormat};
+use rust_api_crud::models::User;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+fn sample_user() -> User {
+    let created_at = Utc.with_ymd_and_hms(2024, 1, 15, 10, 30, 0).unwrap()
+        + chrono::Duration::nanoseconds(123_456_789);
+
+    User {
+        id: Uuid::new_v4(),
+        public_id: None,
+        name: "Alice".to_string(),
+        email: "alice@example.com".to_string(),
+        created_at,
+        updated_at: created_at,
+        name_updated_at: Some(created_at),
+        email_updated_at: None,
+    }
+}
+
+#[tokio::test]
+async fn test_user_timestamp_formats() {
+    let user = sample_user();
+
+    // Default: RFC3339 with full precision
+    let json = serde_json::to_value(&user).unwrap();
+    assert_eq!(json["created_at"], "2024-01-15T10:30:00.123456789Z");
+    assert_eq!(json["updated_at"], "2024-01-15T10:30:00.123456789Z");
+
+    // epoch_ms: milliseconds since the Unix epoch, as a number
+    let json = with_timestamp_format(TimestampFormat::EpochMs, async {
+        serde_json::to_value(&user).unwrap()
+    })
+    .await;
+    assert_eq!(json["created_at"], 1_705_314_600_123_i64);
+    assert_eq!(json["updated_at"], 1_705_314_600_123_i64);
+    assert_eq!(json["name_updated_at"], 1_705_314_600_123_i64);
+    assert!(json["email_updated_at"].is_null());
+
+    // Both representations deserialize back into a User
+    let parsed: User = serde_json::from_value(json).unwrap();
+    assert_eq!(parsed.created_at.timestamp_millis(), 1_705_314_600_123);
+    assert_eq!(parsed.email_updated_at, None);
+
+    // Only inside the scope
+    let json = serde_json::to_value(&user).unwrap();
+    assert_eq!(json["created_at"], "2024-01-15T10:30:00.123456789Z");
+}
+
+#[tokio::test]
+async fn test_app_uses_its_configured_format() {
+    let Some(mut config) = common::try_test_config() else { return };
+    config.features.timestamp_format = TimestampFormat::EpochMs;
+    let url = common::spawn_app_with_config(create_app, config);
+    let client = reqwest::Client::new();
+
+    let created: Value = client
+        .post(format!("{}/users", url))
+        .json(&json!({ "name": "Epoch", "email": format!("epoch-{}@example.com", Uuid::new_v4()) }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert!(created["created_at"].is_i64(), "created_at was {}", created["created_at"]);
+
+    let _ = client.delete(format!("{}/users/{}", url, created["id"].as_str().unwrap())).send().await;
+}
+
+#[test]
+fn test_timestamp_format_parse() {
+    assert_eq!(TimestampFormat::parse("rfc3339"), Some(TimestampFormat::Rfc3339));
+    assert_eq!(TimestampFormat::parse("epoch_ms"), Some(TimestampFormat::EpochMs));
+    assert_eq!(TimestampFormat::parse("unix"), None);
+}
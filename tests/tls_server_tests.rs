@@ -0,0 +1,68 @@
+// HTTPS tests - serve the app over TLS with a freshly generated self-signed cert
+
+use rust_api_crud::config::Config;
+use rust_api_crud::create_app;
+use rust_api_crud::server::{serve_tls, ServerSettings, TlsPaths};
+use tokio::net::TcpListener;
+
+mod common;
+
+// Write a self-signed certificate for localhost to a temp dir
+fn self_signed_cert() -> TlsPaths {
+    let cert = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+
+    let dir = std::env::temp_dir().join(format!("rust-api-crud-tls-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let paths = TlsPaths {
+        cert: dir.join("cert.pem"),
+        key: dir.join("key.pem"),
+    };
+    std::fs::write(&paths.cert, cert.serialize_pem().unwrap()).unwrap();
+    std::fs::write(&paths.key, cert.serialize_private_key_pem()).unwrap();
+
+    paths
+}
+
+#[tokio::test]
+async fn test_https_health_check() {
+    // Arrange: Serve over HTTPS (the health check never touches the database)
+    let tls = self_signed_cert();
+    let pool = common::lazy_pool();
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    tokio::spawn(async move {
+        serve_tls(listener, create_app(pool, Config::default()), ServerSettings::default(), &tls)
+            .await
+            .unwrap();
+    });
+
+    // Act: Self-signed, so the client has to skip verification
+    let client = reqwest::Client::builder()
+        .danger_accept_invalid_certs(true)
+        .build()
+        .unwrap();
+    let response = client
+        .get(format!("https://localhost:{}/health", port))
+        .send()
+        .await
+        .expect("HTTPS request failed");
+
+    // Assert
+    assert_eq!(response.status(), 200);
+}
+
+#[tokio::test]
+async fn test_missing_cert_fails_to_start() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let pool = common::lazy_pool();
+    let tls = TlsPaths {
+        cert: "/nonexistent/cert.pem".into(),
+        key: "/nonexistent/key.pem".into(),
+    };
+
+    let result = serve_tls(listener, create_app(pool, Config::default()), ServerSettings::default(), &tls).await;
+
+    assert!(result.is_err());
+}
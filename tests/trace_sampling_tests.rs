@@ -0,0 +1,41 @@
+// Trace sampling tests - TRACE_SAMPLE_RATE thins out request spans, but
+// failed requests are always traced
+
+mod common;
+
+use rust_api_crud::config::Config;
+use rust_api_crud::create_app_with_state;
+use rust_api_crud::state::AppState;
+
+// One ok (200) and one failed (400) calculator request; returns how many were traced
+async fn traced_requests(rate: f64) -> u64 {
+    // The calculator never touches the database, so a lazy pool is enough
+    let pool = common::lazy_pool();
+    let mut config = Config::default();
+    config.features.trace_sample_rate = rate;
+    let state = AppState::new(pool, config);
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://127.0.0.1:{}", listener.local_addr().unwrap().port());
+    let app = create_app_with_state(state.clone());
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    for (query, status) in [("a=1&b=2&op=add", 200), ("a=1&b=2&op=sqrt", 400)] {
+        let response = reqwest::get(format!("{}/calculate?{}", url, query)).await.unwrap();
+        assert_eq!(response.status(), status);
+    }
+
+    state.metrics.traced_requests()
+}
+
+#[tokio::test]
+async fn test_zero_rate_still_traces_errors() {
+    assert_eq!(traced_requests(0.0).await, 1);
+}
+
+#[tokio::test]
+async fn test_full_rate_traces_everything() {
+    assert_eq!(traced_requests(1.0).await, 2);
+}
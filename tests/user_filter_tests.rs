@@ -0,0 +1,127 @@
+// UserFilter tests - the parameterized WHERE clause behind GET /users
+// Pure unit tests: no database or server needed
+
+use chrono::{TimeZone, Utc};
+use rust_api_crud::models::{FilterValue, UserFilter};
+
+#[test]
+fn test_no_filters_produce_no_where_clause() {
+    let (clause, values) = UserFilter::default().to_sql_where();
+
+    assert_eq!(clause, "");
+    assert!(values.is_empty());
+}
+
+#[test]
+fn test_blank_filters_are_ignored() {
+    let filter = UserFilter {
+        search: Some("   ".to_string()),
+        last_name: Some(String::new()),
+        ..Default::default()
+    };
+
+    let (clause, values) = filter.to_sql_where();
+
+    assert_eq!(clause, "");
+    assert!(values.is_empty());
+}
+
+#[test]
+fn test_search_matches_name_or_email_with_one_placeholder() {
+    let filter = UserFilter {
+        search: Some(" alice ".to_string()),
+        ..Default::default()
+    };
+
+    let (clause, values) = filter.to_sql_where();
+
+    assert_eq!(clause, " WHERE (name ILIKE $1 OR email ILIKE $1)");
+    assert_eq!(values, vec![FilterValue::Text("%alice%".to_string())]);
+}
+
+#[test]
+fn test_search_escapes_like_wildcards() {
+    let filter = UserFilter {
+        search: Some(r"100%_a\b".to_string()),
+        ..Default::default()
+    };
+
+    let (_, values) = filter.to_sql_where();
+
+    assert_eq!(values, vec![FilterValue::Text(r"%100\%\_a\\b%".to_string())]);
+}
+
+#[test]
+fn test_created_range() {
+    let after = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+    let before = Utc.with_ymd_and_hms(2024, 2, 1, 0, 0, 0).unwrap();
+    let filter = UserFilter {
+        created_after: Some(after),
+        created_before: Some(before),
+        ..Default::default()
+    };
+
+    let (clause, values) = filter.to_sql_where();
+
+    assert_eq!(clause, " WHERE created_at > $1 AND created_at < $2");
+    assert_eq!(
+        values,
+        vec![FilterValue::Timestamp(after), FilterValue::Timestamp(before)]
+    );
+}
+
+#[test]
+fn test_all_filters_are_numbered_in_order() {
+    let after = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+    let filter = UserFilter {
+        search: Some("example.com".to_string()),
+        last_name: Some("Smith".to_string()),
+        created_after: Some(after),
+        sort: Some("name:asc".to_string()),
+        ..Default::default()
+    };
+
+    let (clause, values) = filter.to_sql_where();
+
+    assert_eq!(
+        clause,
+        r" WHERE (name ILIKE $1 OR email ILIKE $1) AND lower(regexp_replace(name, '^.*\s', '')) = lower($2) AND created_at > $3"
+    );
+    assert_eq!(
+        values,
+        vec![
+            FilterValue::Text("%example.com%".to_string()),
+            FilterValue::Text("Smith".to_string()),
+            FilterValue::Timestamp(after),
+        ]
+    );
+}
+
+#[test]
+fn test_snapshot_includes_rows_created_at_the_snapshot() {
+    let snapshot = Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap();
+    let filter = UserFilter {
+        snapshot: Some(snapshot),
+        ..Default::default()
+    };
+
+    let (clause, values) = filter.to_sql_where();
+
+    assert_eq!(clause, " WHERE created_at <= $1");
+    assert_eq!(values, vec![FilterValue::Timestamp(snapshot)]);
+}
+
+#[test]
+fn test_ids_bind_as_one_array() {
+    let ids = vec![uuid::Uuid::new_v4(), uuid::Uuid::new_v4()];
+    let filter = UserFilter {
+        search: Some("alice".to_string()),
+        ids: Some(ids.clone()),
+        ..Default::default()
+    };
+
+    let (clause, values) = filter.to_sql_where();
+
+    assert_eq!(clause, " WHERE (name ILIKE $1 OR email ILIKE $1) AND id = ANY($2)");
+    assert_eq!(values[1], FilterValue::Ids(ids));
+}
@@ -0,0 +1,89 @@
+// User quota tests - MAX_USERS caps POST /users
+//
+// The cap is part of the app's Config, so each cap gets its own app. The users
+// table is shared with the other test binaries, so the cap is set relative to
+// the current count.
+
+mod common;
+
+use rust_api_crud::create_app;
+use rust_api_crud::error::ErrorCode;
+use rust_api_crud::models::{ErrorResponse, User};
+use serde_json::json;
+use uuid::Uuid;
+
+async fn user_count(url: &str, client: &reqwest::Client) -> i64 {
+    let body: serde_json::Value = client
+        .get(format!("{}/users/pagination", url))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    body["total"].as_i64().unwrap()
+}
+
+fn spawn_app(max_users: Option<i64>) -> Option<String> {
+    let mut config = common::try_test_config()?;
+    config.limits.max_users = max_users;
+    Some(common::spawn_app_with_config(create_app, config))
+}
+
+#[tokio::test]
+async fn test_max_users_quota() {
+    let Some(url) = spawn_app(None) else { return };
+    let client = reqwest::Client::new();
+    let token = Uuid::new_v4().simple().to_string();
+    let create = |url: &str, i: usize| {
+        client
+            .post(format!("{}/users", url))
+            .json(&json!({
+                "name": format!("Quota {}", i),
+                "email": format!("quota-{}-{}@example.com", i, token)
+            }))
+            .send()
+    };
+
+    // Room for exactly two more users
+    let count = user_count(&url, &client).await;
+    let Some(capped_url) = spawn_app(Some(count + 2)) else { return };
+
+    let mut ids = Vec::new();
+    for i in 0..2 {
+        let response = create(&capped_url, i).await.unwrap();
+        assert_eq!(response.status(), 201);
+        ids.push(response.json::<User>().await.unwrap().id);
+    }
+
+    let response = create(&capped_url, 2).await.unwrap();
+    assert_eq!(response.status(), 403);
+    let body: ErrorResponse = response.json().await.unwrap();
+    assert_eq!(body.error, "user quota exceeded");
+    assert_eq!(body.code, Some(ErrorCode::UserQuotaExceeded));
+
+    // Concurrent creates racing for the last slot: exactly one wins
+    let Some(capped_url) = spawn_app(Some(count + 3)) else { return };
+    let responses = futures::future::join_all((3..8).map(|i| create(&capped_url, i))).await;
+    let mut created = 0;
+    for response in responses {
+        let response = response.unwrap();
+        match response.status().as_u16() {
+            201 => {
+                created += 1;
+                ids.push(response.json::<User>().await.unwrap().id);
+            }
+            status => assert_eq!(status, 403),
+        }
+    }
+    assert_eq!(created, 1);
+
+    // Unset = unlimited
+    let response = create(&url, 8).await.unwrap();
+    assert_eq!(response.status(), 201);
+    ids.push(response.json::<User>().await.unwrap().id);
+
+    for id in ids {
+        let _ = client.delete(format!("{}/users/{}", url, id)).send().await;
+    }
+}
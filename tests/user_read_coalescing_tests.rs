@@ -0,0 +1,63 @@
+// Read coalescing tests - concurrent GET /users/:id for the same id share a
+// single database query
+//
+// The app's pool has one connection, which the test holds while the reads
+// pile up; the first read is stuck waiting for it, so every other read has
+// to join that flight rather than race it.
+
+mod common;
+
+use std::time::Duration;
+
+use futures::future::join_all;
+use rust_api_crud::create_app_with_state;
+use rust_api_crud::models::User;
+use rust_api_crud::state::AppState;
+use serde_json::json;
+use sqlx::postgres::PgPoolOptions;
+use uuid::Uuid;
+
+const READERS: usize = 20;
+
+#[tokio::test]
+async fn test_concurrent_reads_share_one_query() {
+    let Some(config) = common::try_test_config() else { return };
+    let pool = PgPoolOptions::new().max_connections(1).connect(&config.database.url).await.unwrap();
+    let state = AppState::new(pool.clone(), config);
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://127.0.0.1:{}", listener.local_addr().unwrap().port());
+    let app = create_app_with_state(state.clone());
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let client = reqwest::Client::new();
+    let user: User = client
+        .post(format!("{}/users", url))
+        .json(&json!({ "name": "Popular", "email": format!("popular-{}@example.com", Uuid::new_v4()) }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    // Hold the only connection so the first read can't finish before the rest arrive
+    let held = pool.acquire().await.unwrap();
+    let reads: Vec<_> = (0..READERS)
+        .map(|_| tokio::spawn(client.get(format!("{}/users/{}", url, user.id)).send()))
+        .collect();
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    drop(held);
+
+    for read in join_all(reads).await {
+        let response = read.unwrap().unwrap();
+        assert_eq!(response.status(), 200);
+        let fetched: User = response.json().await.unwrap();
+        assert_eq!(fetched.id, user.id);
+    }
+    assert_eq!(state.metrics.user_reads(), 1);
+
+    let _ = client.delete(format!("{}/users/{}", url, user.id)).send().await;
+}
@@ -9,12 +9,17 @@
 // 3. Server running: cargo run (in another terminal)
 //
 // To run: cargo test
+// Without DATABASE_URL (and so without a server) every test here is skipped
 // To run specific test: cargo test test_create_user
 // To see output: cargo test -- --nocapture
 
+mod common;
+
 use serde_json::json;
 use uuid::Uuid;
-use rust_api_crud::models::{User, UserListResponse, ErrorResponse};
+use rust_api_crud::error::ErrorCode;
+use rust_api_crud::models::{ErrorResponse, PaginationMeta, User, UserListResponse};
+use rust_api_crud::validation::FieldError;
 
 const BASE_URL: &str = "http://localhost:3000";
 
@@ -26,12 +31,12 @@ fn client() -> reqwest::Client {
 async fn cleanup_all_users() {
     let client = client();
 
-    if let Ok(response) = client.get(&format!("{}/users", BASE_URL)).send().await {
+    if let Ok(response) = client.get(format!("{}/users", BASE_URL)).send().await {
         if let Ok(result) = response.json::<UserListResponse>().await {
             for user in &result.users {
                 let user_id = user.id.to_string();
                 let _ = client
-                    .delete(&format!("{}/users/{}", BASE_URL, user_id))
+                    .delete(format!("{}/users/{}", BASE_URL, user_id))
                     .send()
                     .await;
             }
@@ -42,7 +47,7 @@ async fn cleanup_all_users() {
 async fn cleanup_user_by_email(email: &str) {
     let client = client();
 
-    let Ok(response) = client.get(&format!("{}/users", BASE_URL)).send().await else {
+    let Ok(response) = client.get(format!("{}/users", BASE_URL)).send().await else {
         return;
     };
 
@@ -58,7 +63,7 @@ async fn cleanup_user_by_email(email: &str) {
         if user.email == email {
             let user_id = user.id.to_string();
             let _ = client
-                .delete(&format!("{}/users/{}", BASE_URL, user_id))
+                .delete(format!("{}/users/{}", BASE_URL, user_id))
                 .send()
                 .await;
         }
@@ -73,12 +78,13 @@ async fn cleanup_user_by_email(email: &str) {
 
 #[tokio::test]
 async fn test_create_user_success() {
+    let Some(_) = common::try_database_url() else { return };
     cleanup_user_by_email("alice@example.com").await;
 
     let client = client();
 
     let response = client
-        .post(&format!("{}/users", BASE_URL))
+        .post(format!("{}/users", BASE_URL))
         .json(&json!({
             "name": "Alice",
             "email": "alice@example.com"
@@ -99,20 +105,82 @@ async fn test_create_user_success() {
 
     let user_id = user.id.to_string();
     let _ = client
-        .delete(&format!("{}/users/{}", BASE_URL, user_id))
+        .delete(format!("{}/users/{}", BASE_URL, user_id))
         .send()
         .await;
 }
 
 
+#[tokio::test]
+async fn test_create_user_return_minimal() {
+    let Some(_) = common::try_database_url() else { return };
+    let client = client();
+    let email = format!("minimal-{}@example.com", Uuid::new_v4());
+
+    let response = client
+        .post(format!("{}/users?return=minimal", BASE_URL))
+        .json(&json!({ "name": "Minimal", "email": email }))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 201);
+    let body: serde_json::Value = response.json().await.unwrap();
+    let object = body.as_object().unwrap();
+    assert_eq!(object.keys().collect::<Vec<_>>(), ["id"]);
+
+    // The id is the real user's
+    let id = object["id"].as_str().unwrap();
+    let user: User = client
+        .get(format!("{}/users/{}", BASE_URL, id))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(user.email, email);
+
+    let _ = client.delete(format!("{}/users/{}", BASE_URL, id)).send().await;
+}
+
+#[tokio::test]
+async fn test_create_user_timestamps_match() {
+    let Some(_) = common::try_database_url() else { return };
+    let client = client();
+    let email = format!("timestamps-{}@example.com", Uuid::new_v4());
+
+    let response = client
+        .post(format!("{}/users", BASE_URL))
+        .json(&json!({
+            "name": "Fresh User",
+            "email": email
+        }))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 201);
+
+    // A user that was never updated has identical timestamps
+    let user: User = response.json().await.unwrap();
+    assert_eq!(user.created_at, user.updated_at);
+
+    let _ = client
+        .delete(format!("{}/users/{}", BASE_URL, user.id))
+        .send()
+        .await;
+}
+
 #[tokio::test]
 async fn test_create_duplicated_user() {
+    let Some(_) = common::try_database_url() else { return };
     cleanup_user_by_email("duplicate@example.com").await;
 
     let client = client();
 
     let response = client
-        .post(&format!("{}/users", BASE_URL))
+        .post(format!("{}/users", BASE_URL))
         .json(&json!({
             "name": "Duplicate User",
             "email": "duplicate@example.com"
@@ -124,7 +192,7 @@ async fn test_create_duplicated_user() {
     let first_user: User = response.json().await.expect("Failed to parse first user");
 
     let response1 = client
-        .post(&format!("{}/users", BASE_URL))
+        .post(format!("{}/users", BASE_URL))
         .json(&json!({
             "name": "Duplicate User",
             "email": "duplicate@example.com"
@@ -138,12 +206,69 @@ async fn test_create_duplicated_user() {
 
     let user_id = first_user.id.to_string();
     let _ = client
-        .delete(&format!("{}/users/{}", BASE_URL, user_id))
+        .delete(format!("{}/users/{}", BASE_URL, user_id))
+        .send()
+        .await;
+}
+
+#[tokio::test]
+async fn test_create_duplicated_user_localized_message() {
+    let Some(_) = common::try_database_url() else { return };
+    cleanup_user_by_email("duplicate-pt@example.com").await;
+
+    let client = client();
+    let payload = json!({
+        "name": "Duplicate PT",
+        "email": "duplicate-pt@example.com"
+    });
+
+    let first = client
+        .post(format!("{}/users", BASE_URL))
+        .json(&payload)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(first.status(), 201);
+    let first_user: User = first.json().await.unwrap();
+
+    let response = client
+        .post(format!("{}/users", BASE_URL))
+        .header("Accept-Language", "pt")
+        .json(&payload)
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 409);
+    let body: ErrorResponse = response.json().await.unwrap();
+    assert_eq!(body.error, "o e-mail já está em uso");
+    // Codes aren't translated
+    assert_eq!(body.code, Some(ErrorCode::EmailTaken));
+
+    let _ = client
+        .delete(format!("{}/users/{}", BASE_URL, first_user.id))
         .send()
         .await;
 }
 
-// TODO: Add test for invalid email format (should return 400)
+#[tokio::test]
+async fn test_create_user_invalid_email() {
+    let Some(_) = common::try_database_url() else { return };
+    let client = client();
+
+    let response = client
+        .post(format!("{}/users", BASE_URL))
+        .json(&json!({ "name": "Invalid", "email": "invalid.example.com" }))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 422);
+    let body: ErrorResponse = response.json().await.unwrap();
+    assert_eq!(body.code, Some(ErrorCode::ValidationFailed));
+    assert_eq!(body.fields.unwrap()[0].field, "email");
+}
+
 // TODO: Add test for missing fields (should return 400)
 
 // ============================================================================
@@ -152,6 +277,7 @@ async fn test_create_duplicated_user() {
 
 #[tokio::test]
 async fn test_get_user_success() {
+    let Some(_) = common::try_database_url() else { return };
     // Cleanup any existing test user with this email
     cleanup_user_by_email("bob@example.com").await;
 
@@ -159,7 +285,7 @@ async fn test_get_user_success() {
 
     // First create a user
     let create_response = client
-        .post(&format!("{}/users", BASE_URL))
+        .post(format!("{}/users", BASE_URL))
         .json(&json!({
             "name": "Bob",
             "email": "bob@example.com"
@@ -173,7 +299,7 @@ async fn test_get_user_success() {
 
     // Now get the user
     let response = client
-        .get(&format!("{}/users/{}", BASE_URL, user_id))
+        .get(format!("{}/users/{}", BASE_URL, user_id))
         .send()
         .await
         .unwrap();
@@ -186,39 +312,193 @@ async fn test_get_user_success() {
 
     // Cleanup
     let _ = client
-        .delete(&format!("{}/users/{}", BASE_URL, user_id))
+        .delete(format!("{}/users/{}", BASE_URL, user_id))
         .send()
         .await;
 }
 
+#[tokio::test]
+async fn test_create_user_redirect_mode() {
+    let Some(_) = common::try_database_url() else { return };
+    // Don't follow the 303, so its status and Location can be checked
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .unwrap();
+
+    let requests = [
+        client.post(format!("{}/users?redirect=true", BASE_URL)),
+        client
+            .post(format!("{}/users", BASE_URL))
+            .header("Accept", "text/html,application/xhtml+xml,*/*;q=0.8"),
+    ];
+
+    for request in requests {
+        let response = request
+            .json(&json!({
+                "name": "Form Post",
+                "email": format!("prg-{}@example.com", Uuid::new_v4())
+            }))
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), 303);
+        let location = response.headers()["location"].to_str().unwrap().to_string();
+        assert!(location.starts_with("/users/"), "Location was {}", location);
+
+        // The redirect target is the created user
+        let user: User = client
+            .get(format!("{}{}", BASE_URL, location))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(location, format!("/users/{}", user.id));
+
+        let _ = client
+            .delete(format!("{}/users/{}", BASE_URL, user.id))
+            .send()
+            .await;
+    }
+}
+
 #[tokio::test]
 async fn test_get_user_not_found() {
+    let Some(_) = common::try_database_url() else { return };
     let client = client();
 
     // Try to get a non-existent user
     let fake_id = Uuid::new_v4();
     let response = client
-        .get(&format!("{}/users/{}", BASE_URL, fake_id))
+        .get(format!("{}/users/{}", BASE_URL, fake_id))
+        .send()
+        .await
+        .unwrap();
+
+    // Should return 404, with a machine-readable code next to the message
+    assert_eq!(response.status(), 404);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body, json!({ "error": "user not found", "code": "USER_NOT_FOUND" }));
+}
+
+#[tokio::test]
+async fn test_get_user_by_email() {
+    let Some(_) = common::try_database_url() else { return };
+    let client = client();
+    let email = format!("by-email-{}@example.com", Uuid::new_v4());
+
+    let created: User = client
+        .post(format!("{}/users", BASE_URL))
+        .json(&json!({ "name": "By Email", "email": email }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    let response = client
+        .get(format!("{}/users/by-email", BASE_URL))
+        .query(&[("email", &email)])
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    let user: User = response.json().await.unwrap();
+    assert_eq!(user.id, created.id);
+
+    let _ = client
+        .delete(format!("{}/users/{}", BASE_URL, created.id))
+        .send()
+        .await;
+}
+
+#[tokio::test]
+async fn test_get_user_by_email_not_found_has_its_own_code() {
+    let Some(_) = common::try_database_url() else { return };
+    let client = client();
+
+    let response = client
+        .get(format!("{}/users/by-email", BASE_URL))
+        .query(&[("email", format!("nobody-{}@example.com", Uuid::new_v4()))])
         .send()
         .await
         .unwrap();
 
-    // Should return 404
+    // Same 404 as a lookup by id, but a different code
     assert_eq!(response.status(), 404);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body, json!({ "error": "no user with that email", "code": "EMAIL_NOT_FOUND" }));
 }
 
 // ============================================================================
 // Phase 2.3: LIST USERS Tests
 // ============================================================================
 
+#[tokio::test]
+async fn test_list_users_summary_view() {
+    let Some(_) = common::try_database_url() else { return };
+    let client = client();
+    let token = Uuid::new_v4().simple().to_string();
+
+    let user: User = client
+        .post(format!("{}/users", BASE_URL))
+        .json(&json!({
+            "name": format!("Summary {}", token),
+            "email": format!("summary-{}@example.com", token)
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    let body: serde_json::Value = client
+        .get(format!("{}/users?view=summary&search={}", BASE_URL, token))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    assert_eq!(body["total"], 1);
+    let row = body["users"][0].as_object().unwrap();
+    let mut keys: Vec<_> = row.keys().map(String::as_str).collect();
+    keys.sort();
+    assert_eq!(keys, ["email", "id", "name"]);
+    assert_eq!(row["id"], user.id.to_string());
+
+    // The default is still the full user
+    let body: serde_json::Value = client
+        .get(format!("{}/users?search={}", BASE_URL, token))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert!(body["users"][0].get("created_at").is_some());
+
+    let _ = client
+        .delete(format!("{}/users/{}", BASE_URL, user.id))
+        .send()
+        .await;
+}
+
 #[tokio::test]
 async fn test_list_users_default_pagination() {
+    let Some(_) = common::try_database_url() else { return };
     let client = client();
 
     // Create a few test users
     for i in 1..=3 {
         let _ = client
-            .post(&format!("{}/users", BASE_URL))
+            .post(format!("{}/users", BASE_URL))
             .json(&json!({
                 "name": format!("User {}", i),
                 "email": format!("user{}@example.com", i)
@@ -228,7 +508,7 @@ async fn test_list_users_default_pagination() {
     }
 
     // List users
-    let response = client.get(&format!("{}/users", BASE_URL)).send().await.unwrap();
+    let response = client.get(format!("{}/users", BASE_URL)).send().await.unwrap();
 
     assert_eq!(response.status(), 200);
 
@@ -241,7 +521,7 @@ async fn test_list_users_default_pagination() {
     for user in &result.users {
         let user_id = user.id.to_string();
         let _ = client
-            .delete(&format!("{}/users/{}", BASE_URL, user_id))
+            .delete(format!("{}/users/{}", BASE_URL, user_id))
             .send()
             .await;
     }
@@ -251,122 +531,1111 @@ async fn test_list_users_default_pagination() {
 // TODO: Add test for empty list
 // TODO: Add test for pagination edge cases
 
-// ============================================================================
-// Phase 2.4: UPDATE USER Tests
-// ============================================================================
-
 #[tokio::test]
-async fn test_update_user_success() {
-    cleanup_user_by_email("original@example.com").await;
-
+async fn test_list_users_multi_column_sort() {
+    let Some(_) = common::try_database_url() else { return };
     let client = client();
+    let suffix = Uuid::new_v4();
 
-    let create_response = client
-        .post(&format!("{}/users", BASE_URL))
-        .json(&json!({
-            "name": "Original Name",
-            "email": "original@example.com"
-        }))
-        .send()
-        .await
-        .unwrap();
-
-    let created_user: User = create_response.json().await.unwrap();
-    let user_id = created_user.id.to_string();
+    // Two users share a name so the second sort key decides their order
+    let mut created = Vec::new();
+    for (name, tag) in [("Sort Beta", "first"), ("Sort Alpha", "second"), ("Sort Alpha", "third")] {
+        let response = client
+            .post(format!("{}/users", BASE_URL))
+            .json(&json!({
+                "name": name,
+                "email": format!("sort-{}-{}@example.com", tag, suffix)
+            }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 201);
+        created.push(response.json::<User>().await.unwrap());
+    }
 
     let response = client
-        .put(&format!("{}/users/{}", BASE_URL, user_id))
-        .json(&json!({
-            "name": "Updated Name"
-        }))
+        .get(format!("{}/users?sort=name:asc,created_at:desc&per_page=100", BASE_URL))
         .send()
         .await
         .unwrap();
 
     assert_eq!(response.status(), 200);
 
-    let updated_user: User = response.json().await.unwrap();
-    assert_eq!(updated_user.name, "Updated Name");
-    assert_eq!(updated_user.email, "original@example.com"); 
-
-    let _ = client
-        .delete(&format!("{}/users/{}", BASE_URL, user_id))
-        .send()
-        .await;
+    let result: UserListResponse = response.json().await.unwrap();
+    let ours: Vec<&User> = result
+        .users
+        .iter()
+        .filter(|user| user.email.ends_with(&format!("-{}@example.com", suffix)))
+        .collect();
+
+    // name ASC puts both "Sort Alpha" first, created_at DESC puts the newest of them on top
+    let emails: Vec<&str> = ours.iter().map(|user| user.email.as_str()).collect();
+    assert_eq!(
+        emails,
+        vec![
+            created[2].email.as_str(),
+            created[1].email.as_str(),
+            created[0].email.as_str(),
+        ]
+    );
+
+    for user in &created {
+        let _ = client
+            .delete(format!("{}/users/{}", BASE_URL, user.id))
+            .send()
+            .await;
+    }
 }
 
 #[tokio::test]
-async fn test_update_user_not_found() {
+async fn test_list_users_filter_by_last_name() {
+    let Some(_) = common::try_database_url() else { return };
     let client = client();
+    let suffix = Uuid::new_v4();
+
+    let mut created = Vec::new();
+    for (name, tag) in [("Alice Smith", "alice"), ("Bob Jones", "bob")] {
+        let user: User = client
+            .post(format!("{}/users", BASE_URL))
+            .json(&json!({
+                "name": name,
+                "email": format!("lastname-{}-{}@example.com", tag, suffix)
+            }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        created.push(user);
+    }
 
-    let fake_id = Uuid::new_v4();
     let response = client
-        .put(&format!("{}/users/{}", BASE_URL, fake_id))
-        .json(&json!({
-            "name": "New Name"
-        }))
+        .get(format!("{}/users?last_name=smith&per_page=100", BASE_URL))
         .send()
         .await
         .unwrap();
 
-    assert_eq!(response.status(), 404);
-}
-
-// TODO: Add test for partial update (only email)
-// TODO: Add test for updating to duplicate email
+    assert_eq!(response.status(), 200);
 
-// ============================================================================
-// Phase 2.5: DELETE USER Tests
-// ============================================================================
+    let result: UserListResponse = response.json().await.unwrap();
+    assert!(result.users.iter().any(|user| user.id == created[0].id));
+    assert!(!result.users.iter().any(|user| user.id == created[1].id));
+    assert!(result
+        .users
+        .iter()
+        .all(|user| user.name.to_lowercase().ends_with("smith")));
+
+    for user in &created {
+        let _ = client
+            .delete(format!("{}/users/{}", BASE_URL, user.id))
+            .send()
+            .await;
+    }
+}
 
 #[tokio::test]
-async fn test_delete_user_success() {
-    cleanup_user_by_email("delete@example.com").await;
-
+async fn test_list_users_search_and_created_range() {
+    let Some(_) = common::try_database_url() else { return };
     let client = client();
+    let suffix = Uuid::new_v4();
 
-    let create_response = client
-        .post(&format!("{}/users", BASE_URL))
+    let user: User = client
+        .post(format!("{}/users", BASE_URL))
         .json(&json!({
-            "name": "To Delete",
-            "email": "delete@example.com"
+            "name": "Search Target",
+            "email": format!("search-{}@example.com", suffix)
         }))
         .send()
         .await
+        .unwrap()
+        .json()
+        .await
         .unwrap();
 
-    let created_user: User = create_response.json().await.unwrap();
-    let user_id = created_user.id.to_string();
-
+    // Matches on the email, and the user was created inside the window
+    let after = (user.created_at - chrono::Duration::seconds(1)).to_rfc3339();
     let response = client
-        .delete(&format!("{}/users/{}", BASE_URL, user_id))
+        .get(format!("{}/users", BASE_URL))
+        .query(&[("search", suffix.to_string()), ("created_after", after)])
         .send()
         .await
         .unwrap();
 
-    assert_eq!(response.status(), 204);
+    assert_eq!(response.status(), 200);
+    let result: UserListResponse = response.json().await.unwrap();
+    assert_eq!(result.total, 1);
+    assert_eq!(result.users[0].id, user.id);
+
+    // Same search, but a window that ends before the user was created
+    let before = (user.created_at - chrono::Duration::seconds(1)).to_rfc3339();
+    let result: UserListResponse = client
+        .get(format!("{}/users", BASE_URL))
+        .query(&[("search", suffix.to_string()), ("created_before", before)])
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(result.total, 0);
 
-    let get_response = client
-        .get(&format!("{}/users/{}", BASE_URL, user_id))
+    let _ = client
+        .delete(format!("{}/users/{}", BASE_URL, user.id))
+        .send()
+        .await;
+}
+
+#[tokio::test]
+async fn test_list_users_empty_search_matches_all() {
+    let Some(_) = common::try_database_url() else { return };
+    let client = client();
+
+    let user: User = client
+        .post(format!("{}/users", BASE_URL))
+        .json(&json!({ "name": "Empty Search", "email": format!("empty-search-{}@example.com", Uuid::new_v4()) }))
         .send()
         .await
+        .unwrap()
+        .json()
+        .await
         .unwrap();
 
-    assert_eq!(get_response.status(), 404);
+    // Pinned to this user's id, so concurrent tests can't change the count;
+    // a cleared search box filters nothing, a real term still does
+    for (search, expected) in [("", 1), ("   ", 1), ("no-such-user-anywhere", 0)] {
+        let response = client
+            .get(format!("{}/users", BASE_URL))
+            .query(&[("id", user.id.to_string()), ("search", search.to_string())])
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+        let result: UserListResponse = response.json().await.unwrap();
+        assert_eq!(result.total, expected, "search={:?}", search);
+    }
+
+    let _ = client
+        .delete(format!("{}/users/{}", BASE_URL, user.id))
+        .send()
+        .await;
+}
+
+#[tokio::test]
+async fn test_list_users_rejects_overlong_search() {
+    let Some(_) = common::try_database_url() else { return };
+    let client = client();
+
+    for path in ["/users", "/users/pagination"] {
+        let response = client
+            .get(format!("{}{}", BASE_URL, path))
+            .query(&[("search", "a".repeat(201))])
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), 400);
+        let body: ErrorResponse = response.json().await.unwrap();
+        assert_eq!(
+            body.fields,
+            Some(vec![FieldError::new("search", "search must be at most 200 characters")])
+        );
+    }
 }
 
 #[tokio::test]
-async fn test_delete_user_not_found() {
+async fn test_list_users_by_repeated_ids() {
+    let Some(_) = common::try_database_url() else { return };
     let client = client();
+    let suffix = Uuid::new_v4();
 
-    let fake_id = Uuid::new_v4();
+    let mut created = Vec::new();
+    for i in 1..=3 {
+        let user: User = client
+            .post(format!("{}/users", BASE_URL))
+            .json(&json!({
+                "name": format!("Ids {}", i),
+                "email": format!("ids{}-{}@example.com", i, suffix)
+            }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        created.push(user);
+    }
+
+    // Two of the three, plus an id nobody has; page is ignored
     let response = client
-        .delete(&format!("{}/users/{}", BASE_URL, fake_id))
+        .get(format!(
+            "{}/users?id={}&id={}&id={}&page=7",
+            BASE_URL,
+            created[0].id,
+            created[2].id,
+            Uuid::new_v4()
+        ))
         .send()
         .await
         .unwrap();
 
-    assert_eq!(response.status(), 404);
+    assert_eq!(response.status(), 200);
+    let result: UserListResponse = response.json().await.unwrap();
+    let mut ids: Vec<Uuid> = result.users.iter().map(|user| user.id).collect();
+    ids.sort();
+    let mut expected = vec![created[0].id, created[2].id];
+    expected.sort();
+    assert_eq!(ids, expected);
+    assert_eq!((result.total, result.page, result.total_pages), (2, 1, 1));
+
+    // A malformed id is a 400 naming it
+    let response = client
+        .get(format!("{}/users?id={}&id=not-an-id", BASE_URL, created[0].id))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+    let body: ErrorResponse = response.json().await.unwrap();
+    assert_eq!(
+        body.fields,
+        Some(vec![FieldError::new("id", "invalid user id: not-an-id")])
+    );
+
+    for user in &created {
+        let _ = client
+            .delete(format!("{}/users/{}", BASE_URL, user.id))
+            .send()
+            .await;
+    }
+}
+
+#[tokio::test]
+async fn test_list_users_rejects_partial_created_after() {
+    let Some(_) = common::try_database_url() else { return };
+    let client = client();
+
+    let response = client
+        .get(format!("{}/users?created_after=2024-01", BASE_URL))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 400);
+    let body: ErrorResponse = response.json().await.unwrap();
+    assert_eq!(body.code, Some(ErrorCode::ValidationFailed));
+    let fields = body.fields.unwrap();
+    assert_eq!(fields.len(), 1);
+    assert_eq!(fields[0].field, "created_after");
+
+    // A bare date is fine
+    let response = client
+        .get(format!("{}/users?created_after=2024-01-31", BASE_URL))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+}
+
+#[tokio::test]
+async fn test_list_users_reports_each_bad_pagination_param() {
+    let Some(_) = common::try_database_url() else { return };
+    let client = client();
+
+    let response = client
+        .get(format!("{}/users?page=0", BASE_URL))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+    let body: ErrorResponse = response.json().await.unwrap();
+    assert_eq!(body.code, Some(ErrorCode::ValidationFailed));
+    assert_eq!(
+        body.fields,
+        Some(vec![FieldError::new("page", "page must be >= 1")])
+    );
+
+    let response = client
+        .get(format!("{}/users?per_page=0", BASE_URL))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+    let body: ErrorResponse = response.json().await.unwrap();
+    assert_eq!(
+        body.fields,
+        Some(vec![FieldError::new("per_page", "per_page must be between 1 and 100")])
+    );
+
+    // Both bad at once: both reported
+    let response = client
+        .get(format!("{}/users/pagination?page=0&per_page=101", BASE_URL))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+    let body: ErrorResponse = response.json().await.unwrap();
+    let fields: Vec<String> = body.fields.unwrap().into_iter().map(|error| error.field).collect();
+    assert_eq!(fields, ["page", "per_page"]);
+}
+
+#[tokio::test]
+async fn test_list_users_rejects_page_beyond_max_offset() {
+    let Some(_) = common::try_database_url() else { return };
+    let client = client();
+
+    // (5002 - 1) * 10 = 50010, past the default MAX_OFFSET of 50000
+    let response = client
+        .get(format!("{}/users?page=5002&per_page=10", BASE_URL))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 400);
+
+    let body: ErrorResponse = response.json().await.unwrap();
+    assert_eq!(body.error, "pagination window too deep, use cursor pagination");
+
+    // The last page inside the window is still allowed
+    let response = client
+        .get(format!("{}/users?page=5001&per_page=10", BASE_URL))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+}
+
+#[tokio::test]
+async fn test_list_users_huge_page_is_out_of_range() {
+    let Some(_) = common::try_database_url() else { return };
+    // (i64::MAX - 1) * 100 overflows; that's a 416, not a panic
+    let response = client()
+        .get(format!("{}/users?page={}&per_page=100", BASE_URL, i64::MAX))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 416);
+    let body: ErrorResponse = response.json().await.unwrap();
+    assert_eq!(body.code, Some(ErrorCode::PageOutOfRange));
+}
+
+#[tokio::test]
+async fn test_db_health_reports_read_and_write() {
+    let Some(_) = common::try_database_url() else { return };
+    let response = client()
+        .get(format!("{}/health/db", BASE_URL))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["read"], "ok");
+    assert_eq!(body["write"], "ok");
+}
+
+#[tokio::test]
+async fn test_get_user_in_requested_timezone() {
+    let Some(_) = common::try_database_url() else { return };
+    let client = client();
+
+    let created: User = client
+        .post(format!("{}/users", BASE_URL))
+        .json(&json!({
+            "name": "Zone Test",
+            "email": format!("tz-{}@example.com", Uuid::new_v4())
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    // Asia/Kolkata is a fixed +05:30 with no daylight saving time
+    let response = client
+        .get(format!("{}/users/{}?tz=Asia/Kolkata", BASE_URL, created.id))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+
+    let body: serde_json::Value = response.json().await.unwrap();
+    let created_at = body["created_at"].as_str().unwrap();
+    assert!(created_at.ends_with("+05:30"), "Expected +05:30, got {}", created_at);
+
+    // Same instant, just displayed in another zone
+    let user: User = serde_json::from_value(body).unwrap();
+    assert_eq!(user.created_at, created.created_at);
+
+    let _ = client
+        .delete(format!("{}/users/{}", BASE_URL, created.id))
+        .send()
+        .await;
+}
+
+#[tokio::test]
+async fn test_unknown_timezone_returns_400() {
+    let Some(_) = common::try_database_url() else { return };
+    let response = client()
+        .get(format!("{}/users?tz=Mars/Olympus_Mons", BASE_URL))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 400);
+
+    let body: ErrorResponse = response.json().await.unwrap();
+    assert_eq!(body.error, "Unknown time zone: Mars/Olympus_Mons");
+}
+
+#[tokio::test]
+async fn test_get_user_by_public_id() {
+    let Some(_) = common::try_database_url() else { return };
+    let client = client();
+
+    let created: User = client
+        .post(format!("{}/users", BASE_URL))
+        .json(&json!({
+            "name": "Public Id",
+            "email": format!("public-id-{}@example.com", Uuid::new_v4())
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    // Populated on create: a short base62 string
+    let public_id = created.public_id.clone().expect("public_id should be set on create");
+    assert!(public_id.len() <= 11);
+    assert!(public_id.chars().all(|c| c.is_ascii_alphanumeric()));
+
+    let response = client
+        .get(format!("{}/users/{}", BASE_URL, public_id))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+
+    let fetched: User = response.json().await.unwrap();
+    assert_eq!(fetched.id, created.id);
+
+    // Unknown public ids are a plain 404
+    let response = client
+        .get(format!("{}/users/{}", BASE_URL, "nope-not-an-id"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 404);
+
+    let _ = client
+        .delete(format!("{}/users/{}", BASE_URL, created.id))
+        .send()
+        .await;
+}
+
+#[tokio::test]
+async fn test_pagination_preview_matches_list() {
+    let Some(_) = common::try_database_url() else { return };
+    let client = client();
+    let token = Uuid::new_v4().simple().to_string();
+
+    let mut ids = Vec::new();
+    for i in 0..3 {
+        let user: User = client
+            .post(format!("{}/users", BASE_URL))
+            .json(&json!({
+                "name": format!("Pager {} {}", token, i),
+                "email": format!("pager-{}-{}@example.com", i, token)
+            }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        ids.push(user.id);
+    }
+
+    let response = client
+        .get(format!("{}/users/pagination?per_page=2&search={}", BASE_URL, token))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+
+    // Metadata only - no users array
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert!(body.get("users").is_none());
+    let meta: PaginationMeta = serde_json::from_value(body).unwrap();
+    assert_eq!((meta.total, meta.per_page, meta.total_pages), (3, 2, 2));
+
+    let list: UserListResponse = client
+        .get(format!("{}/users?per_page=2&search={}", BASE_URL, token))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(list.total, meta.total);
+    assert_eq!(list.per_page, meta.per_page);
+    assert_eq!(list.total_pages, meta.total_pages);
+
+    for id in ids {
+        let _ = client
+            .delete(format!("{}/users/{}", BASE_URL, id))
+            .send()
+            .await;
+    }
+}
+
+#[tokio::test]
+async fn test_list_users_snapshot_excludes_rows_created_mid_pagination() {
+    let Some(_) = common::try_database_url() else { return };
+    let client = client();
+    let token = Uuid::new_v4().simple().to_string();
+    let create = |i: usize| {
+        client
+            .post(format!("{}/users", BASE_URL))
+            .json(&json!({
+                "name": format!("Snapshot {} {}", token, i),
+                "email": format!("snapshot-{}-{}@example.com", i, token)
+            }))
+            .send()
+    };
+
+    let mut ids = Vec::new();
+    for i in 0..3 {
+        ids.push(create(i).await.unwrap().json::<User>().await.unwrap().id);
+    }
+
+    let first_page: serde_json::Value = client
+        .get(format!("{}/users", BASE_URL))
+        .query(&[("per_page", "2"), ("search", token.as_str())])
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(first_page["total"], 3);
+    let snapshot = first_page["snapshot"].as_str().expect("snapshot token").to_string();
+
+    // Rows created while the client is paging
+    let mut late_ids = Vec::new();
+    for i in 3..5 {
+        late_ids.push(create(i).await.unwrap().json::<User>().await.unwrap().id);
+    }
+
+    let second_page: UserListResponse = client
+        .get(format!("{}/users", BASE_URL))
+        .query(&[("per_page", "2"), ("page", "2"), ("search", token.as_str()), ("snapshot", &snapshot)])
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(second_page.total, 3);
+    assert_eq!(second_page.users.len(), 1);
+    assert!(second_page.users.iter().all(|user| !late_ids.contains(&user.id)));
+
+    // Without the snapshot the new rows show up
+    let fresh: UserListResponse = client
+        .get(format!("{}/users", BASE_URL))
+        .query(&[("search", token.as_str())])
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(fresh.total, 5);
+
+    for id in ids.into_iter().chain(late_ids) {
+        let _ = client
+            .delete(format!("{}/users/{}", BASE_URL, id))
+            .send()
+            .await;
+    }
+}
+
+#[tokio::test]
+async fn test_list_users_rejects_unknown_sort_column() {
+    let Some(_) = common::try_database_url() else { return };
+    let client = client();
+
+    let response = client
+        .get(format!("{}/users?sort=name:asc,password:desc", BASE_URL))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 400);
+
+    let body: ErrorResponse = response.json().await.unwrap();
+    assert_eq!(body.error, "Unknown sort column: password");
+}
+
+#[tokio::test]
+async fn test_stream_users_json_array() {
+    let Some(_) = common::try_database_url() else { return };
+    let client = client();
+    let suffix = Uuid::new_v4();
+
+    let mut created = Vec::new();
+    for i in 1..=3 {
+        let user: User = client
+            .post(format!("{}/users", BASE_URL))
+            .json(&json!({
+                "name": format!("Stream {}", i),
+                "email": format!("stream{}-{}@example.com", i, suffix)
+            }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        created.push(user);
+    }
+
+    let response = client
+        .get(format!("{}/users/stream.json", BASE_URL))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["content-type"], "application/json");
+
+    let users: Vec<User> = response.json().await.expect("Stream should be a valid JSON array");
+    assert!(users.len() >= 3);
+    for user in &created {
+        assert!(users.iter().any(|streamed| streamed.id == user.id));
+    }
+
+    for user in &created {
+        let _ = client
+            .delete(format!("{}/users/{}", BASE_URL, user.id))
+            .send()
+            .await;
+    }
+}
+
+// ============================================================================
+// Phase 2.4: UPDATE USER Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_update_user_success() {
+    let Some(_) = common::try_database_url() else { return };
+    cleanup_user_by_email("original@example.com").await;
+
+    let client = client();
+
+    let create_response = client
+        .post(format!("{}/users", BASE_URL))
+        .json(&json!({
+            "name": "Original Name",
+            "email": "original@example.com"
+        }))
+        .send()
+        .await
+        .unwrap();
+
+    let created_user: User = create_response.json().await.unwrap();
+    let user_id = created_user.id.to_string();
+
+    let response = client
+        .put(format!("{}/users/{}", BASE_URL, user_id))
+        .json(&json!({
+            "name": "Updated Name"
+        }))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+
+    let updated_user: User = response.json().await.unwrap();
+    assert_eq!(updated_user.name, "Updated Name");
+    assert_eq!(updated_user.email, "original@example.com"); 
+
+    let _ = client
+        .delete(format!("{}/users/{}", BASE_URL, user_id))
+        .send()
+        .await;
+}
+
+#[tokio::test]
+async fn test_update_user_field_timestamps() {
+    let Some(_) = common::try_database_url() else { return };
+    let client = client();
+    let email = format!("field-ts-{}@example.com", Uuid::new_v4());
+
+    let created: User = client
+        .post(format!("{}/users", BASE_URL))
+        .json(&json!({ "name": "Before", "email": email }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    assert_eq!(created.name_updated_at, None);
+    assert_eq!(created.email_updated_at, None);
+
+    // Change the name, and "change" the email to its current value
+    let response = client
+        .put(format!("{}/users/{}", BASE_URL, created.id))
+        .json(&json!({ "name": "After", "email": email }))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+
+    let updated: User = response.json().await.unwrap();
+    let name_updated_at = updated.name_updated_at.expect("name_updated_at should be set");
+    assert!(name_updated_at >= created.created_at);
+    assert_eq!(updated.email_updated_at, None);
+
+    let _ = client
+        .delete(format!("{}/users/{}", BASE_URL, created.id))
+        .send()
+        .await;
+}
+
+#[tokio::test]
+async fn test_update_user_rejects_null_email() {
+    let Some(_) = common::try_database_url() else { return };
+    let client = client();
+    let email = format!("null-email-{}@example.com", Uuid::new_v4());
+
+    let user: User = client
+        .post(format!("{}/users", BASE_URL))
+        .json(&json!({ "name": "Null Email", "email": email }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    // Explicit null on a non-nullable field is an error, not "keep"
+    let response = client
+        .put(format!("{}/users/{}", BASE_URL, user.id))
+        .json(&json!({ "name": "Renamed", "email": null }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+    let body: ErrorResponse = response.json().await.unwrap();
+    assert_eq!(body.error, "email cannot be null");
+
+    // A present value is checked like on create
+    let response = client
+        .put(format!("{}/users/{}", BASE_URL, user.id))
+        .json(&json!({ "email": "not-an-email" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 422);
+    let body: ErrorResponse = response.json().await.unwrap();
+    assert_eq!(body.code, Some(ErrorCode::ValidationFailed));
+    assert_eq!(body.fields.unwrap()[0].field, "email");
+
+    // An absent email is left as-is
+    let updated: User = client
+        .put(format!("{}/users/{}", BASE_URL, user.id))
+        .json(&json!({ "name": "Renamed" }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(updated.name, "Renamed");
+    assert_eq!(updated.email, email);
+
+    let _ = client
+        .delete(format!("{}/users/{}", BASE_URL, user.id))
+        .send()
+        .await;
+}
+
+#[tokio::test]
+async fn test_update_user_not_found() {
+    let Some(_) = common::try_database_url() else { return };
+    let client = client();
+
+    let fake_id = Uuid::new_v4();
+    let response = client
+        .put(format!("{}/users/{}", BASE_URL, fake_id))
+        .json(&json!({
+            "name": "New Name"
+        }))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 404);
+}
+
+// TODO: Add test for partial update (only email)
+
+#[tokio::test]
+async fn test_update_user_to_duplicate_email() {
+    let Some(_) = common::try_database_url() else { return };
+    cleanup_user_by_email("taken@example.com").await;
+    cleanup_user_by_email("mover@example.com").await;
+
+    let client = client();
+
+    let mut ids = Vec::new();
+    for (name, email) in [("Taken", "taken@example.com"), ("Mover", "mover@example.com")] {
+        let user: User = client
+            .post(format!("{}/users", BASE_URL))
+            .json(&json!({ "name": name, "email": email }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        ids.push(user.id);
+    }
+
+    let response = client
+        .put(format!("{}/users/{}", BASE_URL, ids[1]))
+        .json(&json!({ "email": "taken@example.com" }))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 409);
+    let body: ErrorResponse = response.json().await.unwrap();
+    assert_eq!(body.error, "email already exists");
+    assert_eq!(body.code, Some(ErrorCode::EmailTaken));
+
+    for id in ids {
+        let _ = client
+            .delete(format!("{}/users/{}", BASE_URL, id))
+            .send()
+            .await;
+    }
+}
+
+// ============================================================================
+// Phase 2.5: DELETE USER Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_delete_user_success() {
+    let Some(_) = common::try_database_url() else { return };
+    cleanup_user_by_email("delete@example.com").await;
+
+    let client = client();
+
+    let create_response = client
+        .post(format!("{}/users", BASE_URL))
+        .json(&json!({
+            "name": "To Delete",
+            "email": "delete@example.com"
+        }))
+        .send()
+        .await
+        .unwrap();
+
+    let created_user: User = create_response.json().await.unwrap();
+    let user_id = created_user.id.to_string();
+
+    let response = client
+        .delete(format!("{}/users/{}", BASE_URL, user_id))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 204);
+
+    // Deleted, not "never existed": 410 by id and by public_id
+    let get_response = client
+        .get(format!("{}/users/{}", BASE_URL, user_id))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(get_response.status(), 410);
+    let body: ErrorResponse = get_response.json().await.unwrap();
+    assert_eq!(body.code, Some(ErrorCode::UserDeleted));
+
+    let public_id = created_user.public_id.expect("public_id should be set on create");
+    let get_response = client
+        .get(format!("{}/users/{}", BASE_URL, public_id))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(get_response.status(), 410);
+}
+
+#[tokio::test]
+async fn test_delete_user_not_found() {
+    let Some(_) = common::try_database_url() else { return };
+    let client = client();
+
+    let fake_id = Uuid::new_v4();
+    let response = client
+        .delete(format!("{}/users/{}", BASE_URL, fake_id))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 404);
+}
+
+// ============================================================================
+// Response formatting Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_pretty_json_query_param() {
+    let Some(_) = common::try_database_url() else { return };
+    let client = client();
+    let email = format!("pretty-{}@example.com", Uuid::new_v4());
+
+    let user: User = client
+        .post(format!("{}/users", BASE_URL))
+        .json(&json!({ "name": "Pretty", "email": email }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    let compact = client
+        .get(format!("{}/users/{}", BASE_URL, user.id))
+        .send()
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    assert!(!compact.contains('\n'));
+
+    let pretty = client
+        .get(format!("{}/users/{}?pretty=true", BASE_URL, user.id))
+        .send()
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    assert!(pretty.contains('\n'));
+    assert!(pretty.contains("  \"email\""));
+
+    // The calculator responses are pretty-printed too
+    let calculator = client
+        .get(format!("{}/calculate?a=1&b=2&op=add&pretty=true", BASE_URL))
+        .send()
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    assert!(calculator.contains('\n'));
+
+    let _ = client
+        .delete(format!("{}/users/{}", BASE_URL, user.id))
+        .send()
+        .await;
+}
+
+#[tokio::test]
+async fn test_omit_nulls_query_param() {
+    let Some(_) = common::try_database_url() else { return };
+    let client = client();
+    let email = format!("nulls-{}@example.com", Uuid::new_v4());
+
+    let user: User = client
+        .post(format!("{}/users", BASE_URL))
+        .json(&json!({ "name": "Nulls", "email": email }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    // A new user has never changed their name, so name_updated_at is null
+    let included: serde_json::Value = client
+        .get(format!("{}/users/{}", BASE_URL, user.id))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(included["name_updated_at"], serde_json::Value::Null);
+    assert!(included.as_object().unwrap().contains_key("name_updated_at"));
+
+    let omitted: serde_json::Value = client
+        .get(format!("{}/users/{}?omit_nulls=true", BASE_URL, user.id))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let fields = omitted.as_object().unwrap();
+    assert!(!fields.contains_key("name_updated_at"));
+    assert!(fields.values().all(|value| !value.is_null()));
+    assert_eq!(omitted["email"], email);
+
+    // The streamed list is written on another task, and still honors the flag
+    let streamed: Vec<serde_json::Value> = client
+        .get(format!("{}/users/stream.json?omit_nulls=true", BASE_URL))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let streamed_user = streamed.iter().find(|entry| entry["email"] == email).unwrap();
+    assert!(!streamed_user.as_object().unwrap().contains_key("name_updated_at"));
+
+    let _ = client
+        .delete(format!("{}/users/{}", BASE_URL, user.id))
+        .send()
+        .await;
+}
+
+#[tokio::test]
+async fn test_response_time_header() {
+    let Some(_) = common::try_database_url() else { return };
+    let client = client();
+
+    let response = client
+        .get(format!("{}/users?per_page=1", BASE_URL))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+
+    let header = response
+        .headers()
+        .get("x-response-time-ms")
+        .expect("X-Response-Time-Ms header missing");
+    let elapsed: f64 = header.to_str().unwrap().parse().expect("Header is not a number");
+    assert!(elapsed >= 0.0);
 }
 
 // ============================================================================
@@ -375,12 +1644,13 @@ async fn test_delete_user_not_found() {
 
 #[tokio::test]
 async fn test_complete_user_lifecycle() {
+    let Some(_) = common::try_database_url() else { return };
     cleanup_user_by_email("lifecycle@example.com").await;
 
     let client = client();
 
     let create_response = client
-        .post(&format!("{}/users", BASE_URL))
+        .post(format!("{}/users", BASE_URL))
         .json(&json!({
             "name": "Lifecycle Test",
             "email": "lifecycle@example.com"
@@ -394,14 +1664,14 @@ async fn test_complete_user_lifecycle() {
     let user_id = user.id.to_string();
 
     let get_response = client
-        .get(&format!("{}/users/{}", BASE_URL, user_id))
+        .get(format!("{}/users/{}", BASE_URL, user_id))
         .send()
         .await
         .unwrap();
     assert_eq!(get_response.status(), 200);
 
     let update_response = client
-        .put(&format!("{}/users/{}", BASE_URL, user_id))
+        .put(format!("{}/users/{}", BASE_URL, user_id))
         .json(&json!({
             "name": "Updated Lifecycle"
         }))
@@ -411,25 +1681,25 @@ async fn test_complete_user_lifecycle() {
     assert_eq!(update_response.status(), 200);
 
     let list_response = client
-        .get(&format!("{}/users", BASE_URL))
+        .get(format!("{}/users", BASE_URL))
         .send()
         .await
         .unwrap();
     assert_eq!(list_response.status(), 200);
 
     let delete_response = client
-        .delete(&format!("{}/users/{}", BASE_URL, user_id))
+        .delete(format!("{}/users/{}", BASE_URL, user_id))
         .send()
         .await
         .unwrap();
     assert_eq!(delete_response.status(), 204);
 
     let final_get = client
-        .get(&format!("{}/users/{}", BASE_URL, user_id))
+        .get(format!("{}/users/{}", BASE_URL, user_id))
         .send()
         .await
         .unwrap();
-    assert_eq!(final_get.status(), 404);
+    assert_eq!(final_get.status(), 410);
 }
 
 // ============================================================================
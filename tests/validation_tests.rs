@@ -0,0 +1,226 @@
+// Validation tests - password policy, pagination, search, created-at range and request body rules
+
+use axum::{body::Body, http::Request, routing::post, Router};
+use chrono::{TimeZone, Utc};
+use rust_api_crud::error::ErrorCode;
+use rust_api_crud::models::{
+    CreateUserRequest, CreatedRangeQuery, ErrorResponse, Pagination, UpdateUserRequest,
+};
+use rust_api_crud::validation::{
+    parse_created_range, parse_timestamp_param, validate_pagination, validate_password, validate_search,
+    FieldError, PasswordPolicy, Validate, ValidatedJson,
+};
+use tower::ServiceExt;
+
+#[test]
+fn test_valid_password_passes() {
+    assert_eq!(validate_password("hunter22", &PasswordPolicy::default()), Ok(()));
+}
+
+#[test]
+fn test_too_short_password_is_rejected() {
+    let errors = validate_password("abc12", &PasswordPolicy::default()).unwrap_err();
+
+    assert_eq!(
+        errors,
+        vec![FieldError::new("password", "must be at least 8 characters")]
+    );
+}
+
+#[test]
+fn test_letters_only_password_is_rejected() {
+    let errors = validate_password("onlyletters", &PasswordPolicy::default()).unwrap_err();
+
+    assert_eq!(
+        errors,
+        vec![FieldError::new("password", "must contain at least one digit")]
+    );
+}
+
+#[test]
+fn test_every_broken_rule_is_reported() {
+    let errors = validate_password("123", &PasswordPolicy::default()).unwrap_err();
+
+    assert_eq!(errors.len(), 2);
+    assert!(errors.iter().all(|error| error.field == "password"));
+}
+
+#[test]
+fn test_custom_policy() {
+    let policy = PasswordPolicy {
+        min_length: 12,
+        require_digit: false,
+        require_letter: true,
+    };
+
+    assert!(validate_password("correcthorsebattery", &policy).is_ok());
+    assert!(validate_password("short", &policy).is_err());
+}
+
+#[test]
+fn test_page_zero_is_a_page_error() {
+    let errors = validate_pagination(&Pagination { page: 0, per_page: 10 }).unwrap_err();
+
+    assert_eq!(errors, vec![FieldError::new("page", "page must be >= 1")]);
+}
+
+#[test]
+fn test_per_page_zero_is_a_per_page_error() {
+    let errors = validate_pagination(&Pagination { page: 1, per_page: 0 }).unwrap_err();
+
+    assert_eq!(
+        errors,
+        vec![FieldError::new("per_page", "per_page must be between 1 and 100")]
+    );
+}
+
+#[test]
+fn test_per_page_bounds() {
+    assert!(validate_pagination(&Pagination { page: 1, per_page: 1 }).is_ok());
+    assert!(validate_pagination(&Pagination { page: 1, per_page: 100 }).is_ok());
+    assert!(validate_pagination(&Pagination { page: 1, per_page: 101 }).is_err());
+}
+
+#[test]
+fn test_full_rfc3339_timestamp_is_accepted() {
+    assert_eq!(
+        parse_timestamp_param("created_after", "2024-01-31T12:00:00-03:00"),
+        Ok(Utc.with_ymd_and_hms(2024, 1, 31, 15, 0, 0).unwrap())
+    );
+}
+
+#[test]
+fn test_date_only_is_start_of_day_utc() {
+    assert_eq!(
+        parse_timestamp_param("created_after", "2024-01-31"),
+        Ok(Utc.with_ymd_and_hms(2024, 1, 31, 0, 0, 0).unwrap())
+    );
+}
+
+#[test]
+fn test_partial_dates_are_rejected_naming_the_parameter() {
+    for value in ["2024-01", "2024-1-5", "2024-01-31T12:00:00", "yesterday"] {
+        let error = parse_timestamp_param("created_before", value).unwrap_err();
+        assert_eq!(error.field, "created_before");
+        assert!(error.message.contains(value), "{}", error.message);
+    }
+}
+
+#[test]
+fn test_created_range_reports_both_bounds() {
+    let range = CreatedRangeQuery {
+        created_after: Some("2024-01".to_string()),
+        created_before: Some("2024-13-01".to_string()),
+    };
+    let fields: Vec<String> = parse_created_range(&range)
+        .unwrap_err()
+        .into_iter()
+        .map(|error| error.field)
+        .collect();
+    assert_eq!(fields, ["created_after", "created_before"]);
+
+    // Blank is the same as absent
+    let range = CreatedRangeQuery {
+        created_after: Some(" ".to_string()),
+        created_before: None,
+    };
+    assert_eq!(parse_created_range(&range), Ok((None, None)));
+}
+
+#[test]
+fn test_search_length_is_counted_after_trimming() {
+    let at_limit = format!("  {}  ", "ñ".repeat(200));
+    assert_eq!(validate_search(Some(&at_limit)), Ok(()));
+    assert_eq!(validate_search(Some("   ")), Ok(()));
+    assert_eq!(validate_search(None), Ok(()));
+
+    let errors = validate_search(Some(&"a".repeat(201))).unwrap_err();
+    assert_eq!(errors[0].field, "search");
+}
+
+#[test]
+fn test_create_user_request_rules() {
+    let request = |name: &str, email: &str| CreateUserRequest {
+        name: name.to_string(),
+        email: email.to_string(),
+    };
+
+    assert_eq!(request("Alice", " alice@example.com ").validate(), Ok(()));
+    // Blank names fall back to the email's local part, so they're allowed
+    assert_eq!(request("", "alice@example.com").validate(), Ok(()));
+
+    for email in ["", "alice", "@example.com", "alice@", "a@b@example.com", "al ice@example.com"] {
+        let errors = request("Alice", email).validate().unwrap_err();
+        assert_eq!(errors.len(), 1, "{:?}", email);
+        assert_eq!(errors[0].field, "email");
+    }
+
+    // Every problem at once
+    let fields: Vec<String> = request(&"a".repeat(256), "nope")
+        .validate()
+        .unwrap_err()
+        .into_iter()
+        .map(|error| error.field)
+        .collect();
+    assert_eq!(fields, ["name", "email"]);
+}
+
+#[test]
+fn test_update_user_request_rules() {
+    let request = |body: serde_json::Value| -> UpdateUserRequest { serde_json::from_value(body).unwrap() };
+
+    // Absent and null fields aren't checked here
+    assert_eq!(request(serde_json::json!({})).validate(), Ok(()));
+    assert_eq!(request(serde_json::json!({ "name": null, "email": null })).validate(), Ok(()));
+    assert_eq!(request(serde_json::json!({ "email": "alice@example.com" })).validate(), Ok(()));
+
+    let fields: Vec<String> = request(serde_json::json!({ "name": "a".repeat(256), "email": "nope" }))
+        .validate()
+        .unwrap_err()
+        .into_iter()
+        .map(|error| error.field)
+        .collect();
+    assert_eq!(fields, ["name", "email"]);
+}
+
+// Echoes the name back, so a 200 proves the body reached the handler
+async fn echo(ValidatedJson(request): ValidatedJson<CreateUserRequest>) -> String {
+    request.name
+}
+
+async fn post_body(body: &str) -> axum::response::Response {
+    Router::new()
+        .route("/", post(echo))
+        .oneshot(
+            Request::post("/")
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_validated_json_passes_a_valid_body_through() {
+    let response = post_body(r#"{ "name": "Alice", "email": "alice@example.com" }"#).await;
+
+    assert_eq!(response.status(), 200);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(&body[..], b"Alice");
+}
+
+#[tokio::test]
+async fn test_validated_json_rejects_an_invalid_body() {
+    let response = post_body(r#"{ "name": "Alice", "email": "not-an-email" }"#).await;
+
+    assert_eq!(response.status(), 422);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let body: ErrorResponse = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body.code, Some(ErrorCode::ValidationFailed));
+    assert_eq!(body.fields.unwrap()[0].field, "email");
+
+    // Bodies that don't parse are still rejected like Json<T> rejects them
+    let response = post_body(r#"{ "name": "Alice" }"#).await;
+    assert_eq!(response.status(), 422);
+}